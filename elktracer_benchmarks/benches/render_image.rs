@@ -70,12 +70,12 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             raytracer.render_image(
                 &camera,
                 objects,
-                &RenderOptions {
-                    aspect_ratio,
+                &RenderOptions::new(
                     image_width,
+                    aspect_ratio,
                     samples_per_pixel,
                     max_ray_depth,
-                },
+                ),
             )
         })
     });
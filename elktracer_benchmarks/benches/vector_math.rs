@@ -0,0 +1,128 @@
+use std::{hint::black_box, sync::Arc};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use elktracer_core::{
+    Color, Group, Interval, LambertMaterial, Ray, RayHitTest, Sphere,
+    SphereSet, Vec3f,
+};
+
+const COUNT: usize = 1024;
+const SPHERE_COUNT: usize = 64;
+
+fn vectors(seed: f64) -> Vec<Vec3f> {
+    (0..COUNT)
+        .map(|index| {
+            let t = index as f64 + seed;
+            Vec3f::new(t.sin(), (t * 0.7).cos(), (t * 1.3).sin() + 1.5)
+        })
+        .collect()
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let a = vectors(0.0);
+    let b = vectors(0.5);
+
+    let mut group = c.benchmark_group("vector_math");
+    group.bench_function("dot", |bencher| {
+        bencher.iter(|| {
+            a.iter()
+                .zip(&b)
+                .map(|(a, b)| black_box(a).dot(*b))
+                .sum::<f64>()
+        })
+    });
+    group.bench_function("cross", |bencher| {
+        bencher.iter(|| {
+            a.iter()
+                .zip(&b)
+                .fold(Vec3f::zero(), |sum, (a, b)| sum + black_box(a).cross(*b))
+        })
+    });
+    group.bench_function("unit", |bencher| {
+        bencher.iter(|| {
+            a.iter()
+                .fold(Vec3f::zero(), |sum, a| sum + black_box(a).unit())
+        })
+    });
+    group.bench_function("scale_add", |bencher| {
+        bencher.iter(|| {
+            a.iter().zip(&b).fold(Vec3f::zero(), |sum, (a, b)| {
+                sum + (*black_box(a) - *b) * 0.5 + *b / 3.0
+            })
+        })
+    });
+    group.bench_function("ray_at", |bencher| {
+        let rays = a
+            .iter()
+            .zip(&b)
+            .map(|(origin, direction)| Ray::new(*origin, *direction))
+            .collect::<Vec<_>>();
+        bencher.iter(|| {
+            rays.iter()
+                .fold(Vec3f::zero(), |sum, ray| sum + black_box(ray).at(2.0))
+        })
+    });
+    group.bench_function("sphere_hit", |bencher| {
+        let sphere = Sphere::new(
+            Vec3f::new(0.0, 0.0, 4.0),
+            1.5,
+            Arc::new(LambertMaterial::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let rays = b
+            .iter()
+            .map(|direction| Ray::new(Vec3f::zero(), *direction))
+            .collect::<Vec<_>>();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        bencher.iter(|| {
+            rays.iter()
+                .filter(|ray| sphere.does_hit(black_box(ray), &ray_t).is_some())
+                .count()
+        })
+    });
+    // One ray against many spheres, one by one and in SIMD lanes. Each ray
+    // passes close to the center of a sphere and hits it.
+    let material = Arc::new(LambertMaterial::new(Color::new(0.5, 0.5, 0.5)));
+    let centers = a.iter().take(SPHERE_COUNT).map(|center| *center * 8.0);
+    let spheres = || {
+        centers
+            .clone()
+            .map(|center| Sphere::new(center, 0.3, material.clone()))
+    };
+    let origin = Vec3f::new(0.0, 0.0, -10.0);
+    let rays = centers
+        .clone()
+        .zip(&b)
+        .map(|(center, offset)| {
+            Ray::new(origin, center - origin + *offset * 0.3)
+        })
+        .collect::<Vec<_>>();
+    let ray_t = Interval::new(0.001, f64::INFINITY);
+    let sphere_group = Group::new(
+        spheres()
+            .map(|sphere| Box::new(sphere) as Box<dyn RayHitTest>)
+            .collect(),
+    );
+    group.bench_function("spheres_hit", |bencher| {
+        bencher.iter(|| {
+            rays.iter()
+                .filter(|ray| {
+                    sphere_group.does_hit(black_box(ray), &ray_t).is_some()
+                })
+                .count()
+        })
+    });
+    let sphere_set = SphereSet::new(spheres().collect());
+    group.bench_function("sphere_set_hit", |bencher| {
+        bencher.iter(|| {
+            rays.iter()
+                .filter(|ray| {
+                    sphere_set.does_hit(black_box(ray), &ray_t).is_some()
+                })
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
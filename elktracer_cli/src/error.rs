@@ -3,4 +3,16 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Debug, PartialEq)]
 pub enum Error {
     SceneFileNotFound(String),
+    /// The tile index is not less than the tile count or the image has
+    /// fewer rows than tiles.
+    InvalidTile(u32, u32),
+    Render(elktracer_core::Error),
+    /// The rendered image could not be written to the output file.
+    SaveImage(String),
+}
+
+impl From<elktracer_core::Error> for Error {
+    fn from(value: elktracer_core::Error) -> Self {
+        Error::Render(value)
+    }
 }
@@ -0,0 +1,116 @@
+use std::{
+    fmt,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Inclusive range of animation frames, written as `first..last`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+impl FrameRange {
+    pub fn frames(&self) -> RangeInclusive<u32> {
+        self.first..=self.last
+    }
+
+    /// Inserts the zero padded frame number before the file extension, e.g.
+    /// `out.png` becomes `out_0001.png`.
+    pub fn frame_file_path(&self, output_file: &Path, frame: u32) -> PathBuf {
+        let digits = self.last.to_string().len().max(4);
+        let stem = output_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut file_name = format!("{}_{:0digits$}", stem, frame);
+        if let Some(extension) = output_file.extension() {
+            file_name =
+                format!("{}.{}", file_name, extension.to_string_lossy());
+        }
+        output_file.with_file_name(file_name)
+    }
+}
+
+impl fmt::Display for FrameRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.first, self.last)
+    }
+}
+
+impl FromStr for FrameRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parse_frame = |frame: &str| {
+            frame
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid frame '{}'", frame))
+        };
+
+        let (first, last) = match value.split_once("..") {
+            Some((first, last)) => (
+                parse_frame(first)?,
+                parse_frame(last.trim_start_matches('='))?,
+            ),
+            None => {
+                let frame = parse_frame(value)?;
+                (frame, frame)
+            }
+        };
+        if first > last {
+            return Err(format!("Frame range '{}' is empty", value));
+        }
+
+        Ok(Self { first, last })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_should_parse_ranges_and_single_frames() {
+        let range = |first, last| Ok(FrameRange { first, last });
+
+        assert_eq!("1..24".parse(), range(1, 24));
+        assert_eq!("1..=24".parse(), range(1, 24));
+        assert_eq!(" 3 .. 5 ".parse(), range(3, 5));
+        assert_eq!("7".parse(), range(7, 7));
+    }
+
+    #[test]
+    fn from_str_should_reject_invalid_ranges() {
+        for value in ["", "a..3", "1..", "..5", "-1..2", "5..3", "1...3"] {
+            assert!(value.parse::<FrameRange>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn frame_file_path_should_pad_frame_number() {
+        let frames = FrameRange { first: 1, last: 24 };
+        let output = Path::new("renders/out.png");
+
+        assert_eq!(
+            frames.frame_file_path(output, 7),
+            PathBuf::from("renders/out_0007.png")
+        );
+        assert_eq!(
+            frames.frame_file_path(Path::new("stats"), 12),
+            PathBuf::from("stats_0012")
+        );
+
+        let frames = FrameRange {
+            first: 0,
+            last: 123456,
+        };
+        assert_eq!(
+            frames.frame_file_path(output, 42),
+            PathBuf::from("renders/out_000042.png")
+        );
+    }
+}
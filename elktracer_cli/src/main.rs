@@ -1,12 +1,20 @@
 mod error;
+mod frames;
 mod model;
 
 use self::error::{Error, Result};
+use self::frames::FrameRange;
 
 use clap::{Parser, Subcommand};
-use elktracer_core::Camera;
+use elktracer_core::{
+    Camera, CheckpointSettings, FilterType, IntegratorType, PartialBuffer,
+    RenderOptions, RenderRegion,
+};
+use elktracer_json::animation::{scene_at_frame, validate_animation};
 use elktracer_json::model::SceneModel;
-use elktracer_json::{get_scene_objects, load_scene_model};
+use elktracer_json::{
+    get_scene_objects, load_scene_model, save_render_stats, scene_model_hash,
+};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -32,6 +40,71 @@ enum Commands {
         samples_per_pixel: u16,
         #[arg(long, short = 'r', value_name = "ASPECT_RATIO")]
         max_ray_depth: u16,
+        /// Bounce count after which paths may be terminated early
+        #[arg(long, value_name = "DEPTH", default_value_t = elktracer_core::RenderOptions::DEFAULT_RUSSIAN_ROULETTE_MIN_DEPTH)]
+        russian_roulette_min_depth: u16,
+        /// Light transport: path-tracer, whitted, ambient-occlusion, normals,
+        /// uvs, depth or material-ids
+        #[arg(long, short = 'i', value_name = "INTEGRATOR", default_value_t = IntegratorType::default())]
+        integrator: IntegratorType,
+        /// Traces wavelengths instead of RGB to render dispersion
+        #[arg(long)]
+        spectral: bool,
+        /// Reconstruction filter: box, tent, gaussian or mitchell-netravali
+        #[arg(long, value_name = "FILTER", default_value_t = FilterType::default())]
+        filter: FilterType,
+        /// Filter radius in pixels, defaults to the radius usual for the
+        /// filter
+        #[arg(long, value_name = "PIXELS")]
+        filter_radius: Option<f64>,
+        /// Renders only the pixels inside the rectangle x,y,width,height
+        #[arg(long, value_name = "X,Y,WIDTH,HEIGHT")]
+        region: Option<RenderRegion>,
+        /// Writes only the render region instead of a full size image with
+        /// transparent pixels outside of it
+        #[arg(long, requires = "region")]
+        crop: bool,
+        /// Renders the animation frames FIRST..LAST into a numbered image
+        /// sequence, resuming from the first missing frame
+        #[arg(long, value_name = "FIRST..LAST", conflicts_with = "partial")]
+        frames: Option<FrameRange>,
+        /// Writes the unnormalized sample sums of the render region to the
+        /// output file, to be combined by the merge command
+        #[arg(long, conflicts_with = "crop")]
+        partial: bool,
+        /// Renders the INDEX-th of --tile-count horizontal strips of the image
+        #[arg(long, value_name = "INDEX", requires_all = ["partial", "tile_count"], conflicts_with = "region")]
+        tile_index: Option<u32>,
+        #[arg(long, value_name = "COUNT", requires = "tile_index")]
+        tile_count: Option<u32>,
+        /// Index of the first sample, which seeds the random numbers. Partial
+        /// renders with different offsets are averaged by the merge command
+        #[arg(
+            long,
+            value_name = "SAMPLE",
+            default_value_t = 0,
+            requires = "partial"
+        )]
+        sample_offset: u32,
+        /// Continues from the checkpoint next to the output file, refusing it
+        /// if the scene or the options changed
+        #[arg(long)]
+        resume: bool,
+        /// Seconds between checkpoints, which are saved next to the output
+        /// file as OUTPUT.checkpoint
+        #[arg(long, value_name = "SECONDS", default_value_t = CheckpointSettings::DEFAULT_INTERVAL.as_secs())]
+        checkpoint_interval: u64,
+        /// Writes the render statistics as JSON
+        #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        stats_file: Option<std::path::PathBuf>,
+    },
+    /// Merges partial buffers of render regions or sample ranges into an
+    /// image
+    Merge {
+        #[arg(long, short = 'o', value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        output_file: Option<std::path::PathBuf>,
+        #[arg(required = true, value_name = "PARTIAL_FILES", value_hint = clap::ValueHint::FilePath)]
+        partial_files: Vec<std::path::PathBuf>,
     },
 }
 
@@ -50,6 +123,21 @@ fn main() -> Result<()> {
             aspect_ratio,
             samples_per_pixel,
             max_ray_depth,
+            russian_roulette_min_depth,
+            integrator,
+            spectral,
+            filter,
+            filter_radius,
+            region,
+            crop,
+            frames,
+            partial,
+            tile_index,
+            tile_count,
+            sample_offset,
+            resume,
+            checkpoint_interval,
+            stats_file,
         } => {
             if !scene_file.exists() {
                 log::error!("Scene file does not exist: {:?}", scene_file);
@@ -58,34 +146,105 @@ fn main() -> Result<()> {
                 ));
             }
 
-            let scene: SceneModel = load_scene_model(scene_file);
+            let scene: SceneModel = load_scene_model(scene_file)
+                .inspect_err(|error| log::error!("{}", error))?;
             log::trace!("Parsed scene: {:?}", scene);
 
-            let camera = Camera::from(scene.camera.clone());
-            let objects = get_scene_objects(&scene);
-
-            let mut raytracer = elktracer_core::Raytracer::new();
-            let image = raytracer.render_image(
-                &camera,
-                objects,
-                &elktracer_core::RenderOptions::new(
-                    *image_width,
-                    *aspect_ratio,
-                    *samples_per_pixel,
-                    *max_ray_depth,
-                ),
+            let mut render_options = RenderOptions::new(
+                *image_width,
+                *aspect_ratio,
+                *samples_per_pixel,
+                *max_ray_depth,
             );
+            render_options.russian_roulette_min_depth =
+                *russian_roulette_min_depth;
+            render_options.integrator = *integrator;
+            render_options.spectral = *spectral;
+            render_options.filter = *filter;
+            render_options.filter_radius =
+                filter_radius.unwrap_or(filter.default_radius());
+            render_options.region = *region;
+            render_options.crop_to_region = *crop;
+            render_options.sample_offset = *sample_offset;
+            if let (Some(index), Some(count)) = (tile_index, tile_count) {
+                let Some(tile) = RenderRegion::tile(
+                    render_options.image_width,
+                    render_options.image_height(),
+                    *index,
+                    *count,
+                ) else {
+                    log::error!("Invalid tile {} of {}", index, count);
+                    return Err(Error::InvalidTile(*index, *count));
+                };
+                render_options.region = Some(tile);
+            }
+            if let Err(error) = render_options.validate() {
+                log::error!("Invalid render options: {}", error);
+                return Err(error.into());
+            }
 
             let mut output = std::path::PathBuf::from("out.png");
             if let Some(file) = output_file {
                 output = file.clone();
             }
 
-            let _ = elktracer_core::save_to_file(
+            let settings = RenderSettings {
+                partial: *partial,
+                resume: *resume,
+                checkpoint_interval: std::time::Duration::from_secs(
+                    *checkpoint_interval,
+                ),
+            };
+
+            match frames {
+                Some(frames) => render_frames(
+                    &scene,
+                    &render_options,
+                    frames,
+                    &output,
+                    stats_file.as_deref(),
+                    &settings,
+                )?,
+                None => render_scene(
+                    &scene,
+                    &render_options,
+                    &output,
+                    stats_file.as_deref(),
+                    &settings,
+                )?,
+            }
+        }
+        Commands::Merge {
+            output_file,
+            partial_files,
+        } => {
+            let partials = partial_files
+                .iter()
+                .map(PartialBuffer::load)
+                .collect::<elktracer_core::error::Result<Vec<_>>>()
+                .inspect_err(|error| {
+                    log::error!("Unable to load partial buffer: {}", error)
+                })?;
+            let image =
+                PartialBuffer::merge(&partials).inspect_err(|error| {
+                    log::error!("Unable to merge partial buffers: {}", error)
+                })?;
+
+            let output = output_file
+                .clone()
+                .unwrap_or(std::path::PathBuf::from("out.png"));
+            elktracer_core::save_to_file(
                 &image,
-                output,
+                &output,
                 elktracer_core::image_rs::ImageFormat::Png,
-            );
+            )
+            .inspect_err(|error| {
+                log::error!("Unable to save merged image: {}", error)
+            })
+            .map_err(|error| {
+                Error::SaveImage(format!("{:?}: {}", output, error))
+            })?;
+            log::info!("Merged {} partial buffers", partials.len());
         }
     }
 
@@ -176,3 +335,125 @@ fn main() -> Result<()> {
     //     }
     // }
 }
+
+struct RenderSettings {
+    /// Writes a partial buffer instead of an image.
+    partial: bool,
+    resume: bool,
+    checkpoint_interval: std::time::Duration,
+}
+
+fn render_scene(
+    scene: &SceneModel,
+    render_options: &RenderOptions,
+    output: &std::path::Path,
+    stats_file: Option<&std::path::Path>,
+    settings: &RenderSettings,
+) -> Result<()> {
+    let camera = Camera::try_from(scene.camera.clone())
+        .inspect_err(|error| log::error!("Invalid camera: {}", error))?;
+    let objects = get_scene_objects(scene)
+        .inspect_err(|error| log::error!("Invalid scene: {}", error))?;
+
+    let mut checkpoint_path = output.as_os_str().to_owned();
+    checkpoint_path.push(".checkpoint");
+    let checkpoint = CheckpointSettings::new(
+        checkpoint_path.into(),
+        scene_model_hash(scene),
+    )
+    .with_interval(settings.checkpoint_interval)
+    .with_resume(settings.resume);
+
+    let mut raytracer =
+        elktracer_core::Raytracer::new().with_checkpoint(checkpoint.clone());
+    if settings.partial {
+        let result = raytracer
+            .try_render_partial(
+                &camera,
+                objects,
+                render_options,
+                scene_model_hash(scene),
+            )
+            .inspect_err(|error| {
+                log::error!("Unable to render partial buffer: {}", error)
+            })?;
+        result.partial.save(output)?;
+        remove_checkpoint(&checkpoint);
+
+        println!("{}", result.stats);
+        if let Some(stats_file) = stats_file {
+            save_render_stats(stats_file, &result.stats)
+                .inspect_err(|error| log::error!("{}", error))?;
+        }
+        return Ok(());
+    }
+
+    let result = raytracer
+        .try_render_image(&camera, objects, render_options)
+        .inspect_err(|error| {
+            log::error!("Unable to render image: {}", error)
+        })?;
+
+    elktracer_core::save_to_file(
+        &result.image,
+        output,
+        elktracer_core::image_rs::ImageFormat::Png,
+    )
+    .inspect_err(|error| log::error!("Unable to save image: {}", error))
+    .map_err(|error| Error::SaveImage(format!("{:?}: {}", output, error)))?;
+    remove_checkpoint(&checkpoint);
+
+    println!("{}", result.stats);
+    if let Some(stats_file) = stats_file {
+        save_render_stats(stats_file, &result.stats)
+            .inspect_err(|error| log::error!("{}", error))?;
+    }
+    Ok(())
+}
+
+/// Removes the checkpoint of a render whose output was saved. A checkpoint
+/// left behind only costs disk space, so failures are logged.
+fn remove_checkpoint(checkpoint: &CheckpointSettings) {
+    if let Err(error) = checkpoint.remove() {
+        log::warn!("Unable to remove finished checkpoint: {}", error);
+    }
+}
+
+/// Renders every frame from the first one without an image on disk, so an
+/// interrupted sequence continues where it stopped.
+fn render_frames(
+    scene: &SceneModel,
+    render_options: &RenderOptions,
+    frames: &FrameRange,
+    output: &std::path::Path,
+    stats_file: Option<&std::path::Path>,
+    settings: &RenderSettings,
+) -> Result<()> {
+    let Some(first_missing) = frames
+        .frames()
+        .find(|frame| !frames.frame_file_path(output, *frame).exists())
+    else {
+        log::info!("All frames {} already rendered", frames);
+        return Ok(());
+    };
+    validate_animation(scene)
+        .inspect_err(|error| log::error!("Invalid scene: {}", error))?;
+    if first_missing > frames.first {
+        log::info!("Resuming frames {} at frame {}", frames, first_missing);
+    }
+
+    for frame in first_missing..=frames.last {
+        let frame_output = frames.frame_file_path(output, frame);
+        log::info!("Rendering frame {} to {:?}", frame, frame_output);
+        render_scene(
+            &scene_at_frame(scene, frame as f64)?,
+            render_options,
+            &frame_output,
+            stats_file
+                .map(|stats_file| frames.frame_file_path(stats_file, frame))
+                .as_deref(),
+            settings,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+/// Easing between two keyframes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Cubic bezier timing curve from (0, 0) to (1, 1) with the control
+    /// points (x1, y1) and (x2, y2), as used by CSS transitions.
+    Bezier { x1: f64, y1: f64, x2: f64, y2: f64 },
+}
+
+impl Interpolation {
+    /// Slow start and end, equal to CSS `ease-in-out`.
+    pub const EASE_IN_OUT: Interpolation = Interpolation::Bezier {
+        x1: 0.42,
+        y1: 0.0,
+        x2: 0.58,
+        y2: 1.0,
+    };
+
+    const BEZIER_ITERATIONS: usize = 32;
+
+    /// Maps the linear progress `t` in 0..1 between two keyframes to the
+    /// eased progress.
+    pub fn ease(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Interpolation::Linear => t,
+            Interpolation::Bezier { x1, y1, x2, y2 } => {
+                // x(s) is monotonic for control points inside 0..1, so the
+                // curve parameter is found by bisection.
+                let x1 = x1.clamp(0.0, 1.0);
+                let x2 = x2.clamp(0.0, 1.0);
+                let mut low = 0.0;
+                let mut high = 1.0;
+                for _ in 0..Self::BEZIER_ITERATIONS {
+                    let s = (low + high) / 2.0;
+                    if Self::cubic_bezier(x1, x2, s) < t {
+                        low = s;
+                    } else {
+                        high = s;
+                    }
+                }
+                Self::cubic_bezier(y1, y2, (low + high) / 2.0)
+            }
+        }
+    }
+
+    fn cubic_bezier(p1: f64, p2: f64, s: f64) -> f64 {
+        let inverse = 1.0 - s;
+        3.0 * inverse * inverse * s * p1
+            + 3.0 * inverse * s * s * p2
+            + s * s * s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn ease_should_keep_end_points() {
+        for interpolation in [Interpolation::Linear, Interpolation::EASE_IN_OUT]
+        {
+            assert_approx_eq!(interpolation.ease(0.0), 0.0, 1e-6);
+            assert_approx_eq!(interpolation.ease(1.0), 1.0, 1e-6);
+        }
+    }
+
+    #[test]
+    fn ease_in_out_should_be_symmetric_and_slow_at_start() {
+        let ease = Interpolation::EASE_IN_OUT;
+
+        assert_approx_eq!(ease.ease(0.5), 0.5, 1e-6);
+        assert!(ease.ease(0.1) < 0.1);
+        assert_approx_eq!(ease.ease(0.2) + ease.ease(0.8), 1.0, 1e-6);
+    }
+}
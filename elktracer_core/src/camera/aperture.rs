@@ -0,0 +1,190 @@
+use std::{f64::consts::PI, path::Path, sync::Arc};
+
+use image::DynamicImage;
+
+use crate::{
+    error::{self, Error},
+    math::vector3::Vec3f,
+    random,
+};
+
+/// Shape of the lens opening, which is also the shape of out of focus
+/// highlights.
+#[derive(Debug, Clone, Default)]
+pub enum ApertureShape {
+    #[default]
+    Disk,
+    /// Regular polygon inscribed in the unit disk, formed by `blades`
+    /// diaphragm blades.
+    Polygon {
+        blades: u32,
+        rotation_degrees: f64,
+    },
+    Mask(Arc<ApertureMask>),
+}
+
+impl ApertureShape {
+    /// Returns a point on the aperture, scaled to fit into the unit disk.
+    pub fn sample(&self) -> Vec3f {
+        match self {
+            ApertureShape::Disk => Vec3f::random_in_unit_disk(),
+            ApertureShape::Polygon {
+                blades,
+                rotation_degrees,
+            } => {
+                if *blades < 3 {
+                    return Vec3f::random_in_unit_disk();
+                }
+                Self::sample_polygon(*blades, rotation_degrees.to_radians())
+            }
+            ApertureShape::Mask(mask) => mask.sample(),
+        }
+    }
+
+    /// Samples one of the triangles spanned by the center and two adjacent
+    /// corners, all of which have the same area.
+    fn sample_polygon(blades: u32, rotation: f64) -> Vec3f {
+        let segment_angle = 2.0 * PI / blades as f64;
+        let segment = random::random_u32(0..blades) as f64;
+        let start_angle = rotation + segment * segment_angle;
+        let end_angle = start_angle + segment_angle;
+
+        let mut a = random::random_f64_0_1();
+        let mut b = random::random_f64_0_1();
+        if a + b > 1.0 {
+            a = 1.0 - a;
+            b = 1.0 - b;
+        }
+
+        Vec3f::new(
+            a * start_angle.cos() + b * end_angle.cos(),
+            a * start_angle.sin() + b * end_angle.sin(),
+            0.0,
+        )
+    }
+}
+
+/// Grayscale image of the aperture, where brighter pixels let more light
+/// through. The image is stretched over the square around the unit disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApertureMask {
+    width: u32,
+    height: u32,
+    transmission: Vec<f64>,
+}
+
+impl ApertureMask {
+    const MAX_SAMPLE_ATTEMPTS: usize = 64;
+
+    pub fn new(width: u32, height: u32, transmission: Vec<f64>) -> Self {
+        assert_eq!(
+            transmission.len(),
+            (width * height) as usize,
+            "Aperture mask size does not match its dimensions"
+        );
+        Self {
+            width,
+            height,
+            transmission,
+        }
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> error::Result<Self> {
+        let image = image::open(&path).map_err(|error| {
+            Error::ApertureMask(format!(
+                "{}: {}",
+                path.as_ref().display(),
+                error
+            ))
+        })?;
+        Ok(Self::from(&image))
+    }
+
+    /// Rejection samples the mask. Falls back to the center if no sample is
+    /// accepted, e.g. for an all black mask.
+    fn sample(&self) -> Vec3f {
+        for _ in 0..Self::MAX_SAMPLE_ATTEMPTS {
+            let u = random::random_f64_0_1();
+            let v = random::random_f64_0_1();
+            if random::random_f64_0_1() < self.transmission_at(u, v) {
+                return Vec3f::new(u * 2.0 - 1.0, 1.0 - v * 2.0, 0.0);
+            }
+        }
+        Vec3f::zero()
+    }
+
+    fn transmission_at(&self, u: f64, v: f64) -> f64 {
+        let x = ((u * self.width as f64) as u32).min(self.width - 1);
+        let y = ((v * self.height as f64) as u32).min(self.height - 1);
+        self.transmission[(y * self.width + x) as usize]
+    }
+}
+
+impl From<&DynamicImage> for ApertureMask {
+    fn from(image: &DynamicImage) -> Self {
+        let luma = image.to_luma8();
+        Self::new(
+            luma.width(),
+            luma.height(),
+            luma.pixels()
+                .map(|pixel| pixel.0[0] as f64 / 255.0)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_should_stay_inside_polygon() {
+        let aperture = ApertureShape::Polygon {
+            blades: 6,
+            rotation_degrees: 0.0,
+        };
+        // Radius of the circle inscribed in a hexagon.
+        let apothem = (PI / 6.0).cos();
+
+        for _ in 0..1000 {
+            let p = aperture.sample();
+            for side in 0..6 {
+                let angle = PI / 6.0 + side as f64 * PI / 3.0;
+                let distance = p.x() * angle.cos() + p.y() * angle.sin();
+                assert!(distance <= apothem + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn sample_should_only_return_transmitting_mask_pixels() {
+        // Only the upper left quadrant lets light through.
+        let mask = ApertureMask::new(2, 2, vec![1.0, 0.0, 0.0, 0.0]);
+
+        for _ in 0..100 {
+            let p = mask.sample();
+            assert!(p.x() <= 0.0 && p.y() >= 0.0);
+        }
+    }
+
+    #[test]
+    fn sample_should_repeat_with_random_state() {
+        let aperture = ApertureShape::Polygon {
+            blades: 5,
+            rotation_degrees: 0.0,
+        };
+        let samples = || {
+            random::set_state(7);
+            (0..10).map(|_| aperture.sample()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(samples(), samples());
+    }
+
+    #[test]
+    fn open_should_fail_for_missing_image() {
+        let result = ApertureMask::open("missing_aperture_mask.png");
+
+        assert!(matches!(result, Err(Error::ApertureMask(_))));
+    }
+}
@@ -0,0 +1,59 @@
+use std::f64::consts::PI;
+
+use crate::math::{ray::Ray, vector3::Vec3f};
+
+use super::{Camera, CameraProjection};
+
+/// 360° panorama mapping longitude to x and latitude to y. The field of view
+/// is ignored; images should have an aspect ratio of 2:1.
+pub struct EquirectangularProjection {
+    camera_position: Vec3f,
+    u: Vec3f,
+    v: Vec3f,
+    w: Vec3f,
+    image_width: f64,
+    image_height: f64,
+}
+
+impl EquirectangularProjection {
+    pub fn new(camera: &Camera, image_width: u32, image_height: u32) -> Self {
+        let (u, v, w) = camera.basis();
+
+        Self {
+            camera_position: camera.position,
+            u,
+            v,
+            w,
+            image_width: image_width as f64,
+            image_height: image_height as f64,
+        }
+    }
+}
+
+impl CameraProjection for EquirectangularProjection {
+    fn get_ray(&self, x: f64, y: f64) -> Option<Ray> {
+        let longitude = ((x + 0.5) / self.image_width - 0.5) * 2.0 * PI;
+        let latitude = (0.5 - (y + 0.5) / self.image_height) * PI;
+
+        let direction = self.u * (latitude.cos() * longitude.sin())
+            + self.v * latitude.sin()
+            - self.w * (latitude.cos() * longitude.cos());
+
+        Some(Ray::new(self.camera_position, direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_ray_should_look_forward_in_image_center() {
+        let camera = Camera::default();
+        let projection = EquirectangularProjection::new(&camera, 200, 100);
+
+        let (_, _, w) = camera.basis();
+        let ray = projection.get_ray(99.5, 49.5).unwrap();
+        crate::assert_approx_eq!(ray.direction().unit().dot(-w), 1.0, 1e-9);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::math::{ray::Ray, vector3::Vec3f};
+
+use super::{Camera, CameraProjection};
+
+/// Equidistant fisheye. The vertical field of view is the angle covered by
+/// the image circle, which touches the top and bottom of the image. A field
+/// of view of 180° yields a dome master.
+pub struct FisheyeProjection {
+    camera_position: Vec3f,
+    u: Vec3f,
+    v: Vec3f,
+    w: Vec3f,
+    half_fov: f64,
+    center_x: f64,
+    center_y: f64,
+    radius: f64,
+}
+
+impl FisheyeProjection {
+    pub fn new(camera: &Camera, image_width: u32, image_height: u32) -> Self {
+        let (u, v, w) = camera.basis();
+
+        Self {
+            camera_position: camera.position,
+            u,
+            v,
+            w,
+            half_fov: camera.fov_vertical_degrees.to_radians() / 2.0,
+            center_x: image_width as f64 / 2.0,
+            center_y: image_height as f64 / 2.0,
+            radius: image_height as f64 / 2.0,
+        }
+    }
+}
+
+impl CameraProjection for FisheyeProjection {
+    fn get_ray(&self, x: f64, y: f64) -> Option<Ray> {
+        let nx = (x + 0.5 - self.center_x) / self.radius;
+        let ny = (self.center_y - y - 0.5) / self.radius;
+
+        let r = (nx * nx + ny * ny).sqrt();
+        if r > 1.0 {
+            return None;
+        }
+
+        let theta = r * self.half_fov;
+        let phi = ny.atan2(nx);
+        let direction = (self.u * phi.cos() + self.v * phi.sin()) * theta.sin()
+            - self.w * theta.cos();
+
+        Some(Ray::new(self.camera_position, direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_ray_should_skip_positions_outside_of_image_circle() {
+        let camera = Camera::default();
+        let projection = FisheyeProjection::new(&camera, 200, 100);
+
+        assert!(projection.get_ray(0.0, 0.0).is_none());
+        assert!(projection.get_ray(99.5, 49.5).is_some());
+    }
+}
@@ -0,0 +1,304 @@
+pub mod aperture;
+pub mod equirectangular;
+pub mod fisheye;
+pub mod orthographic;
+pub mod perspective;
+pub mod physical;
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    error::{self, Error},
+    math::{ray::Ray, vector3::Vec3f},
+};
+
+use self::{aperture::ApertureShape, physical::PhysicalCamera};
+
+/// Generates the primary rays of a camera for a fixed image size.
+pub trait CameraProjection: Send + Sync {
+    /// Returns the ray through the image position (`x`, `y`) in pixels, or
+    /// `None` if the position lies outside of the projected image.
+    fn get_ray(&self, x: f64, y: f64) -> Option<Ray>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    Orthographic,
+    Fisheye,
+    Equirectangular,
+}
+
+impl Projection {
+    pub const ALL: [Projection; 4] = [
+        Projection::Perspective,
+        Projection::Orthographic,
+        Projection::Fisheye,
+        Projection::Equirectangular,
+    ];
+
+    pub fn create_projection(
+        &self,
+        camera: &Camera,
+        image_width: u32,
+        image_height: u32,
+    ) -> Box<dyn CameraProjection> {
+        match self {
+            Projection::Perspective => {
+                Box::new(perspective::PerspectiveProjection::new(
+                    camera,
+                    image_width,
+                    image_height,
+                ))
+            }
+            Projection::Orthographic => {
+                Box::new(orthographic::OrthographicProjection::new(
+                    camera,
+                    image_width,
+                    image_height,
+                ))
+            }
+            Projection::Fisheye => Box::new(fisheye::FisheyeProjection::new(
+                camera,
+                image_width,
+                image_height,
+            )),
+            Projection::Equirectangular => {
+                Box::new(equirectangular::EquirectangularProjection::new(
+                    camera,
+                    image_width,
+                    image_height,
+                ))
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Projection::Perspective => "perspective",
+            Projection::Orthographic => "orthographic",
+            Projection::Fisheye => "fisheye",
+            Projection::Equirectangular => "equirectangular",
+        }
+    }
+}
+
+impl fmt::Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Projection {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Projection::ALL
+            .into_iter()
+            .find(|projection| projection.name() == value)
+            .ok_or_else(|| format!("Unknown projection '{}'", value))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Camera {
+    pub position: Vec3f,
+    pub look_at: Vec3f,
+    pub up: Vec3f,
+
+    pub fov_vertical_degrees: f64,
+    pub defocus_angle: f64,
+    pub focus_distance: f64,
+
+    pub projection: Projection,
+    /// Linear scale applied to the rendered radiance.
+    pub exposure: f64,
+
+    pub aperture: ApertureShape,
+    /// Strength of the cat's-eye vignetting towards the image corners, zero
+    /// disables it. Only affects cameras with a defocus angle.
+    pub cat_eye: f64,
+    /// Relative magnification difference between red (700 nm) and blue
+    /// (400 nm) light, zero disables lateral chromatic aberration.
+    pub chromatic_aberration: f64,
+
+    /// Time in seconds at which the shutter opens. Moving objects are
+    /// blurred over the interval until the shutter closes.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+}
+
+impl Camera {
+    pub fn new(
+        position: Vec3f,
+        look_at: Vec3f,
+        up: Vec3f,
+        fov_vertical_degrees: f64,
+        defocus_angle: f64,
+        focus_distance: f64,
+    ) -> Self {
+        Self {
+            position,
+            look_at,
+            up,
+            fov_vertical_degrees,
+            defocus_angle,
+            focus_distance,
+            projection: Projection::default(),
+            exposure: 1.0,
+            aperture: ApertureShape::default(),
+            cat_eye: 0.0,
+            chromatic_aberration: 0.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+        }
+    }
+
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    pub fn with_aperture(mut self, aperture: ApertureShape) -> Self {
+        self.aperture = aperture;
+        self
+    }
+
+    pub fn with_cat_eye(mut self, cat_eye: f64) -> Self {
+        self.cat_eye = cat_eye;
+        self
+    }
+
+    pub fn with_chromatic_aberration(
+        mut self,
+        chromatic_aberration: f64,
+    ) -> Self {
+        self.chromatic_aberration = chromatic_aberration;
+        self
+    }
+
+    pub fn with_shutter(
+        mut self,
+        shutter_open: f64,
+        shutter_close: f64,
+    ) -> Self {
+        self.shutter_open = shutter_open;
+        self.shutter_close = shutter_close;
+        self
+    }
+
+    /// Derives field of view, defocus angle, exposure and the shutter close
+    /// time from a physical description, keeping the focus distance and the
+    /// shutter open time.
+    pub fn with_physical(mut self, physical: &PhysicalCamera) -> Self {
+        self.fov_vertical_degrees = physical.fov_vertical_degrees();
+        self.defocus_angle = physical.defocus_angle(self.focus_distance);
+        self.exposure = physical.exposure();
+        self.shutter_close = self.shutter_open + physical.shutter_seconds;
+        self
+    }
+
+    /// Orthonormal camera basis `(u, v, w)`: `u` points right, `v` up and
+    /// `w` backwards, away from the look at point.
+    pub fn basis(&self) -> (Vec3f, Vec3f, Vec3f) {
+        let w = (self.position - self.look_at).unit();
+        let u = self.up.cross(w).unit();
+        let v = w.cross(u);
+        (u, v, w)
+    }
+
+    /// Checks that the camera orientation defines a basis and that the
+    /// field of view is between 0° and 180°.
+    pub fn validate(&self) -> error::Result<()> {
+        if !(self.fov_vertical_degrees > 0.0
+            && self.fov_vertical_degrees < 180.0)
+        {
+            return Err(Error::InvalidFieldOfView(self.fov_vertical_degrees));
+        }
+        let view = self.position - self.look_at;
+        if view.is_near_zero() {
+            return Err(Error::CameraLooksAtItself);
+        }
+        if self.up.is_near_zero()
+            || self.up.unit().cross(view.unit()).is_near_zero()
+        {
+            return Err(Error::CameraUpParallelToView);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(
+            Vec3f::new(10.0, 2.0, 0.0),
+            Vec3f::new(0.0, 0.0, 0.0),
+            Vec3f::new(0.0, 1.0, 0.0),
+            15.0,
+            0.5,
+            10.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_should_parse_all_names() {
+        for projection in Projection::ALL {
+            assert_eq!(
+                Projection::from_str(&projection.to_string()),
+                Ok(projection)
+            );
+        }
+    }
+
+    #[test]
+    fn validate_should_reject_degenerate_orientation() {
+        assert_eq!(Camera::default().validate(), Ok(()));
+
+        let mut camera = Camera::default();
+        camera.look_at = camera.position;
+        assert_eq!(camera.validate(), Err(Error::CameraLooksAtItself));
+
+        let mut camera = Camera::default();
+        camera.up = camera.look_at - camera.position;
+        assert_eq!(camera.validate(), Err(Error::CameraUpParallelToView));
+
+        camera.up = Vec3f::zero();
+        assert_eq!(camera.validate(), Err(Error::CameraUpParallelToView));
+    }
+
+    #[test]
+    fn validate_should_reject_field_of_view_outside_of_0_to_180_degrees() {
+        let mut camera = Camera {
+            fov_vertical_degrees: 179.0,
+            ..Camera::default()
+        };
+        assert_eq!(camera.validate(), Ok(()));
+
+        for fov in [0.0, -10.0, 180.0, 270.0] {
+            camera.fov_vertical_degrees = fov;
+            assert_eq!(camera.validate(), Err(Error::InvalidFieldOfView(fov)));
+        }
+        camera.fov_vertical_degrees = f64::NAN;
+        assert!(matches!(
+            camera.validate(),
+            Err(Error::InvalidFieldOfView(_))
+        ));
+    }
+
+    #[test]
+    fn basis_should_be_orthonormal() {
+        let (u, v, w) = Camera::default().basis();
+
+        crate::assert_approx_eq!(u.magnitude(), 1.0, 1e-9);
+        crate::assert_approx_eq!(v.magnitude(), 1.0, 1e-9);
+        crate::assert_approx_eq!(u.dot(v), 0.0, 1e-9);
+        crate::assert_approx_eq!(u.dot(w), 0.0, 1e-9);
+        crate::assert_approx_eq!(v.dot(w), 0.0, 1e-9);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::math::{ray::Ray, vector3::Vec3f};
+
+use super::{Camera, CameraProjection};
+
+/// Parallel projection. The view covers the same area as a perspective
+/// camera does in its focus plane, so the field of view and focus distance
+/// together define the visible extent.
+pub struct OrthographicProjection {
+    direction: Vec3f,
+    viewport_upper_left_pixel: Vec3f,
+    viewport_pixel_delta_x: Vec3f,
+    viewport_pixel_delta_y: Vec3f,
+}
+
+impl OrthographicProjection {
+    pub fn new(camera: &Camera, image_width: u32, image_height: u32) -> Self {
+        let viewport_height: f64 = 2.0
+            * (camera.fov_vertical_degrees.to_radians() / 2.0).tan()
+            * camera.focus_distance;
+        let viewport_width: f64 =
+            viewport_height * (image_width as f64 / image_height as f64);
+
+        let (u, v, w) = camera.basis();
+
+        let viewport_edge_x = u * viewport_width;
+        let viewport_edge_y = -v * viewport_height;
+
+        let pixel_delta_x = viewport_edge_x / (image_width as f64);
+        let pixel_delta_y = viewport_edge_y / (image_height as f64);
+
+        let viewport_upper_left =
+            camera.position - viewport_edge_x / 2.0 - viewport_edge_y / 2.0;
+
+        Self {
+            direction: -w,
+            viewport_upper_left_pixel: viewport_upper_left
+                + (pixel_delta_x + pixel_delta_y) * 0.5,
+            viewport_pixel_delta_x: pixel_delta_x,
+            viewport_pixel_delta_y: pixel_delta_y,
+        }
+    }
+}
+
+impl CameraProjection for OrthographicProjection {
+    fn get_ray(&self, x: f64, y: f64) -> Option<Ray> {
+        let origin = self.viewport_upper_left_pixel
+            + (self.viewport_pixel_delta_x * x)
+            + (self.viewport_pixel_delta_y * y);
+
+        Some(Ray::new(origin, self.direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_ray_should_return_parallel_rays() {
+        let camera = Camera::default();
+        let projection = OrthographicProjection::new(&camera, 100, 50);
+
+        let (_, _, w) = camera.basis();
+        for (x, y) in [(0.0, 0.0), (99.0, 0.0), (50.0, 25.0), (0.0, 49.0)] {
+            let ray = projection.get_ray(x, y).unwrap();
+            crate::assert_approx_eq!(ray.direction().unit().dot(-w), 1.0, 1e-9);
+        }
+    }
+}
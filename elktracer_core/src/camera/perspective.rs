@@ -0,0 +1,151 @@
+use crate::math::{ray::Ray, vector3::Vec3f};
+
+use super::{Camera, CameraProjection, aperture::ApertureShape};
+
+/// Thin lens camera. Rays start on the aperture around the camera position
+/// and pass through the viewport in the focus plane.
+pub struct PerspectiveProjection {
+    camera_position: Vec3f,
+    // Defocus / Depth of field
+    defocus_angle: f64,
+    defocus_disk_x: Vec3f,
+    defocus_disk_y: Vec3f,
+    aperture: ApertureShape,
+    // Cat's-eye vignetting
+    cat_eye: f64,
+    image_center: (f64, f64),
+    image_half_diagonal: f64,
+    // Viewport properties
+    viewport_upper_left_pixel: Vec3f,
+    viewport_pixel_delta_x: Vec3f,
+    viewport_pixel_delta_y: Vec3f,
+}
+
+impl PerspectiveProjection {
+    pub fn new(camera: &Camera, image_width: u32, image_height: u32) -> Self {
+        let viewport_height: f64 = 2.0
+            * (camera.fov_vertical_degrees.to_radians() / 2.0).tan()
+            * camera.focus_distance;
+        let viewport_width: f64 =
+            viewport_height * (image_width as f64 / image_height as f64);
+
+        let (u, v, w) = camera.basis();
+
+        let viewport_edge_x = u * viewport_width;
+        let viewport_edge_y = -v * viewport_height;
+
+        let pixel_delta_x = viewport_edge_x / (image_width as f64);
+        let pixel_delta_y = viewport_edge_y / (image_height as f64);
+
+        let viewport_upper_left = camera.position
+            - (w * camera.focus_distance)
+            - viewport_edge_x / 2.0
+            - viewport_edge_y / 2.0;
+
+        let defocus_radius = camera.focus_distance
+            * (camera.defocus_angle / 2.0).to_radians().tan();
+
+        Self {
+            camera_position: camera.position,
+            defocus_angle: camera.defocus_angle,
+            defocus_disk_x: u * defocus_radius,
+            defocus_disk_y: v * defocus_radius,
+            aperture: camera.aperture.clone(),
+            cat_eye: camera.cat_eye,
+            image_center: (
+                (image_width as f64 - 1.0) / 2.0,
+                (image_height as f64 - 1.0) / 2.0,
+            ),
+            image_half_diagonal: (image_width as f64)
+                .hypot(image_height as f64)
+                / 2.0,
+            viewport_upper_left_pixel: viewport_upper_left
+                + (pixel_delta_x + pixel_delta_y) * 0.5,
+            viewport_pixel_delta_x: pixel_delta_x,
+            viewport_pixel_delta_y: pixel_delta_y,
+        }
+    }
+
+    /// Samples the lens opening as seen from the image position (`x`, `y`).
+    /// Off axis, the rear of the lens barrel clips the aperture into a
+    /// cat's-eye shape; blocked samples return `None`.
+    fn aperture_sample(&self, x: f64, y: f64) -> Option<Vec3f> {
+        let p = self.aperture.sample();
+
+        if self.cat_eye > 0.0 {
+            let barrel_offset = Vec3f::new(
+                (x - self.image_center.0) / self.image_half_diagonal,
+                (self.image_center.1 - y) / self.image_half_diagonal,
+                0.0,
+            ) * self.cat_eye;
+            if (p - barrel_offset).magnitude_squared() > 1.0 {
+                return None;
+            }
+        }
+
+        Some(
+            self.camera_position
+                + (self.defocus_disk_x * p.x())
+                + (self.defocus_disk_y * p.y()),
+        )
+    }
+}
+
+impl CameraProjection for PerspectiveProjection {
+    fn get_ray(&self, x: f64, y: f64) -> Option<Ray> {
+        let pixel_sample = self.viewport_upper_left_pixel
+            + (self.viewport_pixel_delta_x * x)
+            + (self.viewport_pixel_delta_y * y);
+
+        let origin = if self.defocus_angle <= 0.0 {
+            self.camera_position
+        } else {
+            self.aperture_sample(x, y)?
+        };
+
+        Some(Ray::new(origin, pixel_sample - origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_ray_should_block_more_samples_in_corners_with_cat_eye() {
+        let camera = Camera::default().with_cat_eye(1.0);
+        let projection = PerspectiveProjection::new(&camera, 100, 100);
+
+        let count_rays = |x: f64, y: f64| {
+            (0..1000)
+                .filter(|_| projection.get_ray(x, y).is_some())
+                .count()
+        };
+
+        assert_eq!(count_rays(49.5, 49.5), 1000);
+        assert!(count_rays(0.0, 0.0) < 900);
+    }
+
+    #[test]
+    fn get_ray_should_sample_defocus_disk_along_both_axes() {
+        // Regression test: the disk used to collapse into a line along the
+        // vertical axis, because its horizontal axis was overwritten.
+        let camera = Camera::default();
+        let projection = PerspectiveProjection::new(&camera, 100, 100);
+        let (u, v, _) = camera.basis();
+        let defocus_radius = camera.focus_distance
+            * (camera.defocus_angle / 2.0).to_radians().tan();
+
+        let (mut max_u, mut max_v) = (0.0_f64, 0.0_f64);
+        for _ in 0..1000 {
+            let ray = projection.get_ray(49.5, 49.5).unwrap();
+            let offset = (ray.origin() - camera.position) / defocus_radius;
+            assert!(offset.magnitude() <= 1.0 + 1e-3);
+            max_u = max_u.max(offset.dot(u).abs());
+            max_v = max_v.max(offset.dot(v).abs());
+        }
+
+        assert!(max_u > 0.5);
+        assert!(max_v > 0.5);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::{self, Error};
+
+/// Camera description in photographic terms. Lengths on the sensor side are
+/// in millimeters, scene units are assumed to be meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicalCamera {
+    pub focal_length_mm: f64,
+    pub sensor_width_mm: f64,
+    pub sensor_height_mm: f64,
+    pub f_number: f64,
+    pub shutter_seconds: f64,
+    pub iso: f64,
+}
+
+impl PhysicalCamera {
+    /// Exposure of the "sunny 16" rule (f/16, 1/100 s, ISO 100), which maps
+    /// to an exposure scale of one.
+    const REFERENCE_EXPOSURE: f64 = 0.01 * 100.0 / (16.0 * 16.0);
+
+    pub fn new(
+        focal_length_mm: f64,
+        sensor_width_mm: f64,
+        sensor_height_mm: f64,
+        f_number: f64,
+        shutter_seconds: f64,
+        iso: f64,
+    ) -> Self {
+        Self {
+            focal_length_mm,
+            sensor_width_mm,
+            sensor_height_mm,
+            f_number,
+            shutter_seconds,
+            iso,
+        }
+    }
+
+    pub fn fov_vertical_degrees(&self) -> f64 {
+        2.0 * (self.sensor_height_mm / (2.0 * self.focal_length_mm))
+            .atan()
+            .to_degrees()
+    }
+
+    pub fn fov_horizontal_degrees(&self) -> f64 {
+        2.0 * (self.sensor_width_mm / (2.0 * self.focal_length_mm))
+            .atan()
+            .to_degrees()
+    }
+
+    /// Radius of the entrance pupil in scene units.
+    pub fn aperture_radius(&self) -> f64 {
+        self.focal_length_mm / (2.0 * self.f_number) / 1000.0
+    }
+
+    /// Defocus cone angle of a lens with this aperture focused at
+    /// `focus_distance`.
+    pub fn defocus_angle(&self, focus_distance: f64) -> f64 {
+        2.0 * (self.aperture_radius() / focus_distance)
+            .atan()
+            .to_degrees()
+    }
+
+    /// Checks that the lens has a positive focal length and f-number, which
+    /// the field of view, aperture and exposure are derived from.
+    pub fn validate(&self) -> error::Result<()> {
+        if !(self.focal_length_mm.is_finite() && self.focal_length_mm > 0.0) {
+            return Err(Error::InvalidFocalLength(self.focal_length_mm));
+        }
+        if !(self.f_number.is_finite() && self.f_number > 0.0) {
+            return Err(Error::InvalidFNumber(self.f_number));
+        }
+        Ok(())
+    }
+
+    /// Linear scale applied to the rendered radiance.
+    pub fn exposure(&self) -> f64 {
+        self.shutter_seconds * self.iso
+            / (self.f_number * self.f_number)
+            / Self::REFERENCE_EXPOSURE
+    }
+}
+
+impl Default for PhysicalCamera {
+    /// 50 mm lens on a full frame sensor at "sunny 16" settings.
+    fn default() -> Self {
+        Self::new(50.0, 36.0, 24.0, 16.0, 0.01, 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fov_should_match_full_frame_normal_lens() {
+        let physical = PhysicalCamera::default();
+
+        crate::assert_approx_eq!(physical.fov_vertical_degrees(), 26.99, 0.01);
+        crate::assert_approx_eq!(
+            physical.fov_horizontal_degrees(),
+            39.60,
+            0.01
+        );
+    }
+
+    #[test]
+    fn exposure_should_double_per_stop() {
+        let physical = PhysicalCamera::default();
+        let longer_shutter = PhysicalCamera {
+            shutter_seconds: 0.02,
+            ..physical
+        };
+
+        crate::assert_approx_eq!(physical.exposure(), 1.0, 1e-9);
+        crate::assert_approx_eq!(longer_shutter.exposure(), 2.0, 1e-9);
+    }
+
+    #[test]
+    fn aperture_radius_should_be_half_the_pupil_diameter() {
+        let physical = PhysicalCamera {
+            f_number: 2.0,
+            ..PhysicalCamera::default()
+        };
+
+        crate::assert_approx_eq!(physical.aperture_radius(), 0.0125, 1e-9);
+    }
+
+    #[test]
+    fn validate_should_reject_non_positive_focal_length_and_f_number() {
+        assert_eq!(PhysicalCamera::default().validate(), Ok(()));
+
+        let physical = PhysicalCamera {
+            focal_length_mm: 0.0,
+            ..PhysicalCamera::default()
+        };
+        assert_eq!(physical.validate(), Err(Error::InvalidFocalLength(0.0)));
+
+        let physical = PhysicalCamera {
+            f_number: -2.0,
+            ..PhysicalCamera::default()
+        };
+        assert_eq!(physical.validate(), Err(Error::InvalidFNumber(-2.0)));
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::{
+    error::{self, Error},
+    film::AccumulationBuffer,
+    raytracer::{RenderOptions, RenderRegion},
+    utils::read_array,
+};
+
+const MAGIC: &[u8; 8] = b"ELKCKPT\0";
+const VERSION: u32 = 2;
+
+/// Where and how often `render_image` saves its progress, and whether it
+/// continues from a previously saved checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointSettings {
+    pub path: PathBuf,
+    pub interval: Duration,
+    /// Identifies the scene the checkpoint belongs to, see `hash`.
+    pub scene_hash: u64,
+    /// Continues from the checkpoint file if it exists.
+    pub resume: bool,
+}
+
+impl CheckpointSettings {
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+    pub fn new(path: PathBuf, scene_hash: u64) -> Self {
+        Self {
+            path,
+            interval: Self::DEFAULT_INTERVAL,
+            scene_hash,
+            resume: false,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Removes the checkpoint, if there is one. Renders keep their last
+    /// checkpoint, so that callers remove it only once the result is saved
+    /// and a failed save can still be resumed.
+    pub fn remove(&self) -> error::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(Error::Checkpoint(format!(
+                    "{}: {}",
+                    self.path.display(),
+                    error
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// FNV-1a hash of a serialized scene. Unlike the hasher of the standard
+    /// library it is stable across builds.
+    pub fn hash(data: &[u8]) -> u64 {
+        data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+}
+
+/// Progress of a render besides the accumulated samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CheckpointState {
+    /// Samples per pixel already in the accumulation buffer.
+    pub completed_samples: u16,
+    pub rng_state: u64,
+}
+
+/// Hash of all options, a checkpoint only continues the same render.
+fn options_hash(options: &RenderOptions) -> u64 {
+    let mut bytes = options.sample_bytes();
+    let region = options.region.unwrap_or(RenderRegion::new(0, 0, 0, 0));
+    bytes.push(options.region.is_some() as u8);
+    for value in [region.x, region.y, region.width, region.height] {
+        bytes.extend(value.to_le_bytes());
+    }
+    bytes.push(options.crop_to_region as u8);
+    bytes.extend(options.samples_per_pixel.to_le_bytes());
+    bytes.extend(options.sample_offset.to_le_bytes());
+    CheckpointSettings::hash(&bytes)
+}
+
+/// Writes the checkpoint next to its path first, so that an interruption
+/// never leaves a partially written checkpoint behind.
+pub(crate) fn save(
+    settings: &CheckpointSettings,
+    options: &RenderOptions,
+    state: CheckpointState,
+    buffer: &AccumulationBuffer,
+) -> io::Result<()> {
+    let mut temporary_path = settings.path.clone().into_os_string();
+    temporary_path.push(".tmp");
+    let temporary_path = PathBuf::from(temporary_path);
+
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&settings.scene_hash.to_le_bytes())?;
+    writer.write_all(&options_hash(options).to_le_bytes())?;
+    writer.write_all(&state.completed_samples.to_le_bytes())?;
+    writer.write_all(&state.rng_state.to_le_bytes())?;
+    buffer.write_to(&mut writer)?;
+    writer.into_inner()?.sync_all()?;
+
+    fs::rename(&temporary_path, &settings.path)
+}
+
+/// Reads the checkpoint into the buffer, refusing it if it was saved for a
+/// different scene or different options.
+pub(crate) fn load(
+    settings: &CheckpointSettings,
+    options: &RenderOptions,
+    buffer: &mut AccumulationBuffer,
+) -> error::Result<CheckpointState> {
+    let io_error = |error: io::Error| {
+        Error::Checkpoint(format!("{}: {}", settings.path.display(), error))
+    };
+    let mut reader =
+        BufReader::new(File::open(&settings.path).map_err(io_error)?);
+
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic).map_err(io_error)?;
+    let version = u32::from_le_bytes(read_array(&mut reader, io_error)?);
+    if &magic != MAGIC || version != VERSION {
+        return Err(Error::Checkpoint(format!(
+            "{} is not a checkpoint of this version",
+            settings.path.display()
+        )));
+    }
+
+    let scene_hash = u64::from_le_bytes(read_array(&mut reader, io_error)?);
+    if scene_hash != settings.scene_hash {
+        return Err(Error::CheckpointSceneChanged);
+    }
+    let saved_options_hash =
+        u64::from_le_bytes(read_array(&mut reader, io_error)?);
+    if saved_options_hash != options_hash(options) {
+        return Err(Error::CheckpointOptionsChanged);
+    }
+
+    let state = CheckpointState {
+        completed_samples: u16::from_le_bytes(read_array(
+            &mut reader,
+            io_error,
+        )?),
+        rng_state: u64::from_le_bytes(read_array(&mut reader, io_error)?),
+    };
+    buffer.read_from(&mut reader).map_err(io_error)?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Color, filter::FilterType, integrator::IntegratorType};
+
+    fn buffer(options: &RenderOptions) -> AccumulationBuffer {
+        AccumulationBuffer::new(
+            RenderRegion::new(
+                0,
+                0,
+                options.image_width,
+                options.image_height(),
+            ),
+            options.filter,
+            options.filter_radius,
+        )
+        .unwrap()
+    }
+
+    fn settings(name: &str) -> CheckpointSettings {
+        CheckpointSettings::new(
+            std::env::temp_dir().join(format!(
+                "elktracer_{}_{}.checkpoint",
+                name,
+                std::process::id()
+            )),
+            42,
+        )
+    }
+
+    #[test]
+    fn hash_should_be_fnv1a() {
+        assert_eq!(CheckpointSettings::hash(b""), 0xcbf29ce484222325);
+        assert_eq!(CheckpointSettings::hash(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn options_hash_should_change_with_every_option() {
+        let options = RenderOptions::new(4, 2.0, 8, 1);
+        let changes: [fn(&mut RenderOptions); 12] = [
+            |options| options.image_width = 5,
+            |options| options.aspect_ratio = 1.0,
+            |options| options.samples_per_pixel = 9,
+            |options| options.max_ray_depth = 2,
+            |options| options.russian_roulette_min_depth = 1,
+            |options| options.integrator = IntegratorType::Normals,
+            |options| options.spectral = true,
+            |options| options.region = Some(RenderRegion::new(0, 0, 0, 0)),
+            |options| options.crop_to_region = true,
+            |options| options.filter = FilterType::Gaussian,
+            |options| options.filter_radius = 1.5,
+            |options| options.sample_offset = 8,
+        ];
+
+        for change in changes {
+            let mut changed = options;
+            change(&mut changed);
+            assert_ne!(options_hash(&changed), options_hash(&options));
+        }
+    }
+
+    #[test]
+    fn load_should_restore_saved_state() {
+        let settings = settings("restore");
+        let options = RenderOptions::new(4, 2.0, 8, 1);
+        let mut saved = buffer(&options);
+        saved.splat(1.0, 1.0, Color::new(0.25, 0.5, 1.0));
+        let state = CheckpointState {
+            completed_samples: 3,
+            rng_state: 1234,
+        };
+
+        save(&settings, &options, state, &saved).unwrap();
+        let mut loaded = buffer(&options);
+        let loaded_state = load(&settings, &options, &mut loaded);
+        fs::remove_file(&settings.path).unwrap();
+
+        assert_eq!(loaded_state, Ok(state));
+        assert_eq!(loaded.resolve(1, 1), Color::new(0.25, 0.5, 1.0));
+    }
+
+    #[test]
+    fn load_should_refuse_changed_scene_or_options() {
+        let settings = settings("refuse");
+        let options = RenderOptions::new(4, 2.0, 8, 1);
+        let state = CheckpointState {
+            completed_samples: 1,
+            rng_state: 0,
+        };
+        save(&settings, &options, state, &buffer(&options)).unwrap();
+
+        let changed_scene = CheckpointSettings {
+            scene_hash: 7,
+            ..settings.clone()
+        };
+        let changed_options = RenderOptions::new(4, 2.0, 16, 1);
+        let scene_result =
+            load(&changed_scene, &options, &mut buffer(&options));
+        let options_result =
+            load(&settings, &changed_options, &mut buffer(&options));
+        fs::remove_file(&settings.path).unwrap();
+
+        assert_eq!(scene_result, Err(Error::CheckpointSceneChanged));
+        assert_eq!(options_result, Err(Error::CheckpointOptionsChanged));
+    }
+
+    #[test]
+    fn remove_should_delete_checkpoint_once() {
+        let settings = settings("remove");
+        let options = RenderOptions::new(4, 2.0, 8, 1);
+        let state = CheckpointState {
+            completed_samples: 1,
+            rng_state: 0,
+        };
+        save(&settings, &options, state, &buffer(&options)).unwrap();
+
+        assert_eq!(settings.remove(), Ok(()));
+        assert!(!settings.path.exists());
+        assert_eq!(settings.remove(), Ok(()));
+    }
+}
@@ -25,6 +25,26 @@ impl Color {
         )
     }
 
+    pub fn r(&self) -> f64 {
+        self.r
+    }
+
+    pub fn g(&self) -> f64 {
+        self.g
+    }
+
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.r.is_finite() && self.g.is_finite() && self.b.is_finite()
+    }
+
+    pub fn max_component(&self) -> f64 {
+        f64::max(self.r, f64::max(self.g, self.b))
+    }
+
     fn linear_to_gamma(linear_component: f64) -> f64 {
         if linear_component > 0.0 {
             f64::sqrt(linear_component)
@@ -103,6 +123,13 @@ mod tests {
         assert_eq!(color.b, 1.0);
     }
 
+    #[test]
+    fn max_component_should_be_correct() {
+        assert_eq!(Color::new(0.2, 0.7, 0.1).max_component(), 0.7);
+        assert_eq!(Color::new(0.0, 0.0, 0.0).max_component(), 0.0);
+        assert_eq!(Color::new(0.9, 0.5, 1.5).max_component(), 1.5);
+    }
+
     #[test]
     fn as_rgb_should_be_correct() {
         // TODO
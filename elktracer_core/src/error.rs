@@ -1,4 +1,166 @@
-// pub type Result<T> = core::result::Result<T, Error>;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
-pub enum Error {}
+use crate::raytracer::RenderRegion;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    InvalidImageWidth(u32),
+    InvalidAspectRatio(f64),
+    /// The image width divided by the aspect ratio is less than one pixel.
+    InvalidImageHeight {
+        image_width: u32,
+        aspect_ratio: f64,
+    },
+    InvalidSamplesPerPixel(u16),
+    InvalidFilterRadius(f64),
+    /// The render region does not contain any pixel of the image.
+    EmptyRenderRegion(RenderRegion),
+    /// The scene file could not be read or parsed.
+    SceneFile(String),
+    /// An object or material references a material ID that doesn't exist.
+    MaterialNotFound(String),
+    /// Materials reference each other, e.g. `a -> b -> a`.
+    MaterialReferenceCycle(String),
+    /// An instance references a group ID that doesn't exist.
+    GroupNotFound(String),
+    /// Groups instance each other, e.g. `a -> b -> a`.
+    GroupReferenceCycle(String),
+    /// The object with the ID is scaled by zero along an axis.
+    ZeroObjectScale(String),
+    /// An animation track targets a missing object or property, or its
+    /// keyframe values don't have the type of the property.
+    InvalidAnimation(String),
+    /// The aperture mask image could not be loaded.
+    ApertureMask(String),
+    /// The camera position and the point it looks at are the same.
+    CameraLooksAtItself,
+    /// The up vector is zero or parallel to the view direction.
+    CameraUpParallelToView,
+    /// The vertical field of view in degrees is not between 0 and 180.
+    InvalidFieldOfView(f64),
+    /// The focal length of the physical camera is not positive.
+    InvalidFocalLength(f64),
+    /// The f-number of the physical camera is not positive.
+    InvalidFNumber(f64),
+    /// The pixel buffer size overflows or could not be allocated.
+    ImageAllocation {
+        width: u32,
+        height: u32,
+    },
+    /// The checkpoint file could not be read or written.
+    Checkpoint(String),
+    /// The checkpoint was saved for a different scene.
+    CheckpointSceneChanged,
+    /// The checkpoint was saved with different render options.
+    CheckpointOptionsChanged,
+    /// The partial buffer file could not be read or written.
+    PartialBuffer(String),
+    /// The render statistics could not be written.
+    RenderStatsFile(String),
+    /// The partial buffers do not belong to the same image.
+    IncompatiblePartialBuffers(String),
+    /// The render was cancelled through its progress handle.
+    RenderCancelled,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidImageWidth(width) => {
+                write!(f, "Image width {} must be at least one pixel", width)
+            }
+            Error::InvalidAspectRatio(aspect_ratio) => write!(
+                f,
+                "Aspect ratio {} must be a positive number",
+                aspect_ratio
+            ),
+            Error::InvalidImageHeight {
+                image_width,
+                aspect_ratio,
+            } => write!(
+                f,
+                "Image width {} with aspect ratio {} results in an image height of zero",
+                image_width, aspect_ratio
+            ),
+            Error::InvalidSamplesPerPixel(samples) => {
+                write!(f, "Samples per pixel {} must be at least one", samples)
+            }
+            Error::InvalidFilterRadius(radius) => {
+                write!(f, "Filter radius {} must be a positive number", radius)
+            }
+            Error::EmptyRenderRegion(region) => {
+                write!(f, "Render region {} is outside of the image", region)
+            }
+            Error::SceneFile(message) => {
+                write!(f, "Invalid scene file {}", message)
+            }
+            Error::MaterialNotFound(id) => {
+                write!(f, "Material {:?} not found", id)
+            }
+            Error::MaterialReferenceCycle(cycle) => {
+                write!(f, "Material reference cycle: {}", cycle)
+            }
+            Error::GroupNotFound(id) => write!(f, "Group {:?} not found", id),
+            Error::GroupReferenceCycle(cycle) => {
+                write!(f, "Group reference cycle: {}", cycle)
+            }
+            Error::ZeroObjectScale(id) => {
+                write!(f, "Object {:?} has a zero scale", id)
+            }
+            Error::InvalidAnimation(message) => {
+                write!(f, "Invalid animation: {}", message)
+            }
+            Error::ApertureMask(message) => {
+                write!(f, "Unable to load aperture mask {}", message)
+            }
+            Error::CameraLooksAtItself => {
+                write!(f, "Camera position and look at point are the same")
+            }
+            Error::CameraUpParallelToView => write!(
+                f,
+                "Camera up vector is zero or parallel to the view direction"
+            ),
+            Error::InvalidFieldOfView(fov) => write!(
+                f,
+                "Vertical field of view {}° must be between 0° and 180°",
+                fov
+            ),
+            Error::InvalidFocalLength(focal_length) => write!(
+                f,
+                "Focal length {} mm must be a positive number",
+                focal_length
+            ),
+            Error::InvalidFNumber(f_number) => {
+                write!(f, "F-number {} must be a positive number", f_number)
+            }
+            Error::ImageAllocation { width, height } => write!(
+                f,
+                "Unable to allocate an image of {}x{} pixels",
+                width, height
+            ),
+            Error::Checkpoint(message) => {
+                write!(f, "Invalid checkpoint {}", message)
+            }
+            Error::CheckpointSceneChanged => {
+                write!(f, "Checkpoint was saved for a different scene")
+            }
+            Error::CheckpointOptionsChanged => {
+                write!(f, "Checkpoint was saved with different render options")
+            }
+            Error::PartialBuffer(message) => {
+                write!(f, "Invalid partial buffer {}", message)
+            }
+            Error::RenderStatsFile(message) => {
+                write!(f, "Unable to save render stats {}", message)
+            }
+            Error::IncompatiblePartialBuffers(message) => {
+                write!(f, "Unable to merge partial buffers: {}", message)
+            }
+            Error::RenderCancelled => write!(f, "Render was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,197 @@
+use std::io::{self, Read, Write};
+
+use crate::{
+    color::Color,
+    error::{Error, Result},
+    filter::FilterType,
+    raytracer::RenderRegion,
+    utils::try_filled_vec,
+};
+
+/// Weighted sums of filtered samples for the pixels of a render region.
+pub struct AccumulationBuffer {
+    region: RenderRegion,
+    colors: Vec<Color>,
+    weights: Vec<f64>,
+    filter: FilterType,
+    filter_radius: f64,
+}
+
+impl AccumulationBuffer {
+    pub fn new(
+        region: RenderRegion,
+        filter: FilterType,
+        filter_radius: f64,
+    ) -> Result<Self> {
+        let allocation_error = Error::ImageAllocation {
+            width: region.width,
+            height: region.height,
+        };
+        let pixel_count = (region.width as usize)
+            .checked_mul(region.height as usize)
+            .ok_or(allocation_error.clone())?;
+        Ok(Self {
+            region,
+            colors: try_filled_vec(pixel_count, Color::new(0.0, 0.0, 0.0))
+                .ok_or(allocation_error.clone())?,
+            weights: try_filled_vec(pixel_count, 0.0)
+                .ok_or(allocation_error)?,
+            filter,
+            filter_radius,
+        })
+    }
+
+    /// Adds a sample at image position (`x`, `y`) to all pixels whose center
+    /// lies within the filter radius. Pixel centers are at integer positions.
+    pub fn splat(&mut self, x: f64, y: f64, color: Color) {
+        let region_end_x = (self.region.x + self.region.width) as f64 - 1.0;
+        let region_end_y = (self.region.y + self.region.height) as f64 - 1.0;
+        let min_x = (x - self.filter_radius).ceil().max(self.region.x as f64);
+        let max_x = (x + self.filter_radius).floor().min(region_end_x);
+        let min_y = (y - self.filter_radius).ceil().max(self.region.y as f64);
+        let max_y = (y + self.filter_radius).floor().min(region_end_y);
+        if min_x > max_x || min_y > max_y {
+            return;
+        }
+
+        for pixel_y in min_y as u32..=max_y as u32 {
+            for pixel_x in min_x as u32..=max_x as u32 {
+                let weight = self.filter.evaluate(
+                    pixel_x as f64 - x,
+                    pixel_y as f64 - y,
+                    self.filter_radius,
+                );
+                if weight == 0.0 {
+                    continue;
+                }
+
+                let index = self.index(pixel_x, pixel_y);
+                self.colors[index] += color * weight;
+                self.weights[index] += weight;
+            }
+        }
+    }
+
+    /// Filtered color of the pixel, black if no sample contributed to it.
+    /// Samples in the negative lobes of a filter can leave a pixel with a
+    /// negative or vanishing total weight, which would flip or blow up the
+    /// color, so such pixels count as having no contribution as well.
+    pub fn resolve(&self, x: u32, y: u32) -> Color {
+        let index = self.index(x, y);
+        let weight = self.weights[index];
+        if weight <= f64::EPSILON {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        self.colors[index] * (1.0 / weight)
+    }
+
+    /// Adds the sums of the pixels the other buffer shares with this one.
+    pub fn add(&mut self, other: &AccumulationBuffer) {
+        let start_x = self.region.x.max(other.region.x);
+        let start_y = self.region.y.max(other.region.y);
+        let end_x = (self.region.x + self.region.width)
+            .min(other.region.x + other.region.width);
+        let end_y = (self.region.y + self.region.height)
+            .min(other.region.y + other.region.height);
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let index = self.index(x, y);
+                let other_index = other.index(x, y);
+                self.colors[index] += other.colors[other_index];
+                self.weights[index] += other.weights[other_index];
+            }
+        }
+    }
+
+    pub fn region(&self) -> RenderRegion {
+        self.region
+    }
+
+    /// Writes the sums of all pixels as little endian `f64` values.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        for (color, weight) in self.colors.iter().zip(&self.weights) {
+            for value in [color.r(), color.g(), color.b(), *weight] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the sums of all pixels with values written by `write_to`
+    /// for a region of the same size.
+    pub fn read_from(&mut self, reader: &mut impl Read) -> io::Result<()> {
+        let mut read_f64 = || -> io::Result<f64> {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(f64::from_le_bytes(bytes))
+        };
+        for (color, weight) in self.colors.iter_mut().zip(&mut self.weights) {
+            *color = Color::new(read_f64()?, read_f64()?, read_f64()?);
+            *weight = read_f64()?;
+        }
+        Ok(())
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        ((y - self.region.y) * self.region.width + (x - self.region.x)) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn splat_with_box_filter_should_average_samples_of_pixel() {
+        let mut buffer = AccumulationBuffer::new(
+            RenderRegion::new(0, 0, 2, 1),
+            FilterType::Box,
+            FilterType::Box.default_radius(),
+        )
+        .unwrap();
+
+        buffer.splat(0.2, 0.1, Color::new(1.0, 0.0, 0.0));
+        buffer.splat(-0.3, 0.0, Color::new(0.0, 1.0, 0.0));
+        buffer.splat(1.0, 0.0, Color::new(0.0, 0.0, 1.0));
+
+        assert_eq!(buffer.resolve(0, 0), Color::new(0.5, 0.5, 0.0));
+        assert_eq!(buffer.resolve(1, 0), Color::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn splat_with_tent_filter_should_reach_neighbours() {
+        let mut buffer = AccumulationBuffer::new(
+            RenderRegion::new(10, 10, 2, 1),
+            FilterType::Tent,
+            1.0,
+        )
+        .unwrap();
+
+        buffer.splat(10.25, 10.0, Color::new(1.0, 1.0, 1.0));
+        buffer.splat(11.0, 10.0, Color::new(0.0, 0.0, 0.0));
+
+        // Pixel 10 only sees the white sample, pixel 11 both with weights
+        // 0.25 and 1.
+        assert_approx_eq!(buffer.resolve(10, 10).r(), 1.0, 1e-9);
+        assert_approx_eq!(buffer.resolve(11, 10).r(), 0.2, 1e-9);
+    }
+
+    #[test]
+    fn resolve_should_ignore_negative_total_weight() {
+        let filter = FilterType::MitchellNetravali;
+        let mut buffer = AccumulationBuffer::new(
+            RenderRegion::new(0, 0, 1, 1),
+            filter,
+            filter.default_radius(),
+        )
+        .unwrap();
+
+        // Only lands in the negative lobe of the pixel at the origin.
+        assert!(filter.evaluate(1.5, 0.0, filter.default_radius()) < 0.0);
+        buffer.splat(1.5, 0.0, Color::new(1.0, 1.0, 1.0));
+
+        assert_eq!(buffer.resolve(0, 0), Color::new(0.0, 0.0, 0.0));
+    }
+}
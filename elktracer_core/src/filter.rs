@@ -0,0 +1,132 @@
+use std::{fmt, str::FromStr};
+
+/// Reconstruction filter weighting each sample by its distance to the
+/// pixel centers it contributes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterType {
+    #[default]
+    Box,
+    Tent,
+    Gaussian,
+    MitchellNetravali,
+}
+
+impl FilterType {
+    pub const ALL: [FilterType; 4] = [
+        FilterType::Box,
+        FilterType::Tent,
+        FilterType::Gaussian,
+        FilterType::MitchellNetravali,
+    ];
+
+    /// Radius in pixels commonly used with the filter.
+    pub fn default_radius(&self) -> f64 {
+        match self {
+            FilterType::Box => 0.5,
+            FilterType::Tent => 1.0,
+            FilterType::Gaussian => 1.5,
+            FilterType::MitchellNetravali => 2.0,
+        }
+    }
+
+    /// Weight of a sample at offset (`x`, `y`) pixels from a pixel center.
+    pub fn evaluate(&self, x: f64, y: f64, radius: f64) -> f64 {
+        self.evaluate_1d(x, radius) * self.evaluate_1d(y, radius)
+    }
+
+    fn evaluate_1d(&self, x: f64, radius: f64) -> f64 {
+        let x = x.abs();
+        if x > radius {
+            return 0.0;
+        }
+
+        match self {
+            FilterType::Box => 1.0,
+            FilterType::Tent => radius - x,
+            FilterType::Gaussian => {
+                // Shifted down so the filter reaches zero at its radius.
+                let sigma = radius / 2.0;
+                let gaussian = |x: f64| (-x * x / (2.0 * sigma * sigma)).exp();
+                gaussian(x) - gaussian(radius)
+            }
+            FilterType::MitchellNetravali => Self::mitchell(2.0 * x / radius),
+        }
+    }
+
+    /// Mitchell-Netravali cubic with B = C = 1/3 over 0..2.
+    fn mitchell(x: f64) -> f64 {
+        const B: f64 = 1.0 / 3.0;
+        const C: f64 = 1.0 / 3.0;
+
+        let value = if x < 1.0 {
+            (12.0 - 9.0 * B - 6.0 * C) * x * x * x
+                + (-18.0 + 12.0 * B + 6.0 * C) * x * x
+                + (6.0 - 2.0 * B)
+        } else {
+            (-B - 6.0 * C) * x * x * x
+                + (6.0 * B + 30.0 * C) * x * x
+                + (-12.0 * B - 48.0 * C) * x
+                + (8.0 * B + 24.0 * C)
+        };
+        value / 6.0
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterType::Box => "box",
+            FilterType::Tent => "tent",
+            FilterType::Gaussian => "gaussian",
+            FilterType::MitchellNetravali => "mitchell-netravali",
+        }
+    }
+}
+
+impl fmt::Display for FilterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for FilterType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        FilterType::ALL
+            .into_iter()
+            .find(|filter| filter.name() == value)
+            .ok_or_else(|| format!("Unknown filter '{}'", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn from_str_should_parse_all_names() {
+        for filter in FilterType::ALL {
+            assert_eq!(FilterType::from_str(&filter.to_string()), Ok(filter));
+        }
+    }
+
+    #[test]
+    fn evaluate_should_be_zero_outside_of_radius() {
+        for filter in FilterType::ALL {
+            let radius = filter.default_radius();
+            assert_eq!(filter.evaluate(radius + 0.01, 0.0, radius), 0.0);
+            assert!(filter.evaluate(0.0, 0.0, radius) > 0.0);
+        }
+    }
+
+    #[test]
+    fn mitchell_should_be_continuous_and_have_negative_lobe() {
+        assert_approx_eq!(
+            FilterType::mitchell(1.0 - 1e-9),
+            FilterType::mitchell(1.0 + 1e-9),
+            1e-6
+        );
+        assert_approx_eq!(FilterType::mitchell(2.0), 0.0, 1e-9);
+        assert!(FilterType::mitchell(1.5) < 0.0);
+    }
+}
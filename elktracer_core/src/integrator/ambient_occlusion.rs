@@ -0,0 +1,55 @@
+use crate::{
+    color::Color,
+    math::{interval::Interval, ray::Ray, vector3::Vec3f},
+    raytracer::RenderOptions,
+    scene::Scene,
+};
+
+use super::Integrator;
+
+/// Shades each primary hit by the fraction of cosine weighted hemisphere
+/// rays that do not hit anything within `max_distance`.
+pub struct AmbientOcclusionIntegrator {
+    max_distance: f64,
+}
+
+impl AmbientOcclusionIntegrator {
+    pub fn new(max_distance: f64) -> Self {
+        Self { max_distance }
+    }
+}
+
+impl Default for AmbientOcclusionIntegrator {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        _options: &RenderOptions,
+    ) -> Color {
+        let Some(ray_hit) =
+            scene.does_hit(ray, &Interval::new(0.001, f64::INFINITY))
+        else {
+            return Color::new(1.0, 1.0, 1.0);
+        };
+
+        let mut direction = ray_hit.normal() + Vec3f::random_unit();
+        if direction.is_near_zero() {
+            direction = ray_hit.normal();
+        }
+
+        let occlusion_ray =
+            Ray::new(ray_hit.point(), direction.unit()).with_time(ray.time());
+        match scene
+            .does_hit(&occlusion_ray, &Interval::new(0.001, self.max_distance))
+        {
+            Some(_) => Color::new(0.0, 0.0, 0.0),
+            None => Color::new(1.0, 1.0, 1.0),
+        }
+    }
+}
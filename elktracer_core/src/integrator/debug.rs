@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::{
+    color::Color,
+    math::{interval::Interval, ray::Ray},
+    raytracer::RenderOptions,
+    scene::Scene,
+};
+
+use super::Integrator;
+
+/// Visualizes the shading normal of the primary hit mapped to 0..1.
+pub struct NormalsIntegrator;
+
+impl Integrator for NormalsIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        _options: &RenderOptions,
+    ) -> Color {
+        match scene.does_hit(ray, &Interval::new(0.001, f64::INFINITY)) {
+            Some(ray_hit) => {
+                let normal = ray_hit.normal();
+                Color::new(
+                    (normal.x() + 1.0) * 0.5,
+                    (normal.y() + 1.0) * 0.5,
+                    (normal.z() + 1.0) * 0.5,
+                )
+            }
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Visualizes the surface coordinates of the primary hit as red and green.
+pub struct UvIntegrator;
+
+impl Integrator for UvIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        _options: &RenderOptions,
+    ) -> Color {
+        match scene.does_hit(ray, &Interval::new(0.001, f64::INFINITY)) {
+            Some(ray_hit) => {
+                let (u, v) = ray_hit.uv();
+                Color::new(u, v, 0.0)
+            }
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Visualizes the distance to the primary hit, white being closest and
+/// black being `max_distance` or further.
+pub struct DepthIntegrator {
+    max_distance: f64,
+}
+
+impl DepthIntegrator {
+    pub fn new(max_distance: f64) -> Self {
+        Self { max_distance }
+    }
+}
+
+impl Default for DepthIntegrator {
+    fn default() -> Self {
+        Self::new(20.0)
+    }
+}
+
+impl Integrator for DepthIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        _options: &RenderOptions,
+    ) -> Color {
+        match scene.does_hit(ray, &Interval::new(0.001, f64::INFINITY)) {
+            Some(ray_hit) => {
+                let distance = ray_hit.t() * ray.direction().magnitude();
+                let depth = 1.0 - f64::min(distance / self.max_distance, 1.0);
+                Color::new(depth, depth, depth)
+            }
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+/// Gives every distinct material instance its own flat color.
+pub struct MaterialIdIntegrator;
+
+impl MaterialIdIntegrator {
+    fn id_to_color(id: usize) -> Color {
+        // splitmix64 finalizer to spread nearby addresses over the palette
+        let mut hash = id as u64;
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^= hash >> 31;
+
+        Color::new(
+            (hash & 0xff) as f64 / 255.0,
+            ((hash >> 8) & 0xff) as f64 / 255.0,
+            ((hash >> 16) & 0xff) as f64 / 255.0,
+        )
+    }
+}
+
+impl Integrator for MaterialIdIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        _options: &RenderOptions,
+    ) -> Color {
+        match scene.does_hit(ray, &Interval::new(0.001, f64::INFINITY)) {
+            Some(ray_hit) => Self::id_to_color(Arc::as_ptr(&ray_hit.material)
+                as *const ()
+                as usize),
+            None => Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+pub mod ambient_occlusion;
+pub mod debug;
+pub mod path_tracer;
+pub mod whitted;
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    color::Color, math::ray::Ray, raytracer::RenderOptions, scene::Scene,
+};
+
+pub trait Integrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        options: &RenderOptions,
+    ) -> Color;
+
+    /// Whether the integrator handles rays carrying a wavelength. Such
+    /// integrators return the radiance at that wavelength in every channel.
+    fn supports_spectral(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegratorType {
+    #[default]
+    PathTracer,
+    Whitted,
+    AmbientOcclusion,
+    Normals,
+    Uvs,
+    Depth,
+    MaterialIds,
+}
+
+impl IntegratorType {
+    pub const ALL: [IntegratorType; 7] = [
+        IntegratorType::PathTracer,
+        IntegratorType::Whitted,
+        IntegratorType::AmbientOcclusion,
+        IntegratorType::Normals,
+        IntegratorType::Uvs,
+        IntegratorType::Depth,
+        IntegratorType::MaterialIds,
+    ];
+
+    pub fn create_integrator(&self) -> Box<dyn Integrator> {
+        match self {
+            IntegratorType::PathTracer => {
+                Box::new(path_tracer::PathTracerIntegrator)
+            }
+            IntegratorType::Whitted => {
+                Box::new(whitted::WhittedIntegrator::default())
+            }
+            IntegratorType::AmbientOcclusion => Box::new(
+                ambient_occlusion::AmbientOcclusionIntegrator::default(),
+            ),
+            IntegratorType::Normals => Box::new(debug::NormalsIntegrator),
+            IntegratorType::Uvs => Box::new(debug::UvIntegrator),
+            IntegratorType::Depth => {
+                Box::new(debug::DepthIntegrator::default())
+            }
+            IntegratorType::MaterialIds => {
+                Box::new(debug::MaterialIdIntegrator)
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntegratorType::PathTracer => "path-tracer",
+            IntegratorType::Whitted => "whitted",
+            IntegratorType::AmbientOcclusion => "ambient-occlusion",
+            IntegratorType::Normals => "normals",
+            IntegratorType::Uvs => "uvs",
+            IntegratorType::Depth => "depth",
+            IntegratorType::MaterialIds => "material-ids",
+        }
+    }
+}
+
+impl fmt::Display for IntegratorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for IntegratorType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        IntegratorType::ALL
+            .into_iter()
+            .find(|integrator| integrator.name() == value)
+            .ok_or_else(|| format!("Unknown integrator '{}'", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_should_parse_all_names() {
+        for integrator in IntegratorType::ALL {
+            assert_eq!(
+                IntegratorType::from_str(&integrator.to_string()),
+                Ok(integrator)
+            );
+        }
+    }
+
+    #[test]
+    fn from_str_should_reject_unknown_names() {
+        assert!(IntegratorType::from_str("photon-mapper").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    color::Color,
+    math::{interval::Interval, ray::Ray},
+    random,
+    raytracer::RenderOptions,
+    scene::Scene,
+    spectrum,
+};
+
+use super::Integrator;
+
+/// Unidirectional path tracer following material scattering until the ray
+/// escapes to the background, gets absorbed or is terminated.
+pub struct PathTracerIntegrator;
+
+impl Integrator for PathTracerIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        options: &RenderOptions,
+    ) -> Color {
+        let mut ray = *ray;
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+
+        for depth in 0..options.max_ray_depth {
+            let Some(ray_hit) =
+                scene.does_hit(&ray, &Interval::new(0.001, f64::INFINITY))
+            else {
+                return throughput
+                    * spectrum::for_wavelength(
+                        scene.background_color(&ray),
+                        ray.wavelength(),
+                    );
+            };
+
+            let Some((scattered, attenuation)) = ray_hit.material.scatter(
+                &ray,
+                ray_hit.point(),
+                ray_hit.normal(),
+                ray_hit.is_front_face(),
+            ) else {
+                return Color::new(0.0, 0.0, 0.0);
+            };
+
+            throughput = throughput
+                * spectrum::for_wavelength(attenuation, ray.wavelength());
+            ray = scattered
+                .with_wavelength(ray.wavelength())
+                .with_time(ray.time());
+
+            // Russian roulette: randomly terminate low energy paths and
+            // boost the survivors so the estimate stays unbiased.
+            if depth >= options.russian_roulette_min_depth {
+                let survival_probability =
+                    f64::min(throughput.max_component(), 0.95);
+                if random::random_f64_0_1() >= survival_probability {
+                    return Color::new(0.0, 0.0, 0.0);
+                }
+                throughput = throughput * (1.0 / survival_probability);
+            }
+        }
+
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    fn supports_spectral(&self) -> bool {
+        true
+    }
+}
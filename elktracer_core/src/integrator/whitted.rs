@@ -0,0 +1,100 @@
+use crate::{
+    color::Color,
+    math::{interval::Interval, ray::Ray, vector3::Vec3f},
+    raytracer::RenderOptions,
+    scene::Scene,
+    spectrum,
+};
+
+use super::Integrator;
+
+/// Whitted-style tracer: specular materials are followed recursively while
+/// diffuse surfaces are shaded directly by a single directional light with
+/// a shadow ray. The light takes the background color in its direction.
+pub struct WhittedIntegrator {
+    light_direction: Vec3f,
+    ambient: f64,
+}
+
+impl WhittedIntegrator {
+    pub fn new(light_direction: Vec3f, ambient: f64) -> Self {
+        Self {
+            light_direction: light_direction.unit(),
+            ambient,
+        }
+    }
+}
+
+impl Default for WhittedIntegrator {
+    fn default() -> Self {
+        Self::new(Vec3f::new(0.3, 1.0, 0.2), 0.1)
+    }
+}
+
+impl Integrator for WhittedIntegrator {
+    fn calculate_color(
+        &self,
+        scene: &mut Scene,
+        ray: &Ray,
+        options: &RenderOptions,
+    ) -> Color {
+        let mut ray = *ray;
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+
+        for _depth in 0..options.max_ray_depth {
+            let Some(ray_hit) =
+                scene.does_hit(&ray, &Interval::new(0.001, f64::INFINITY))
+            else {
+                return throughput
+                    * spectrum::for_wavelength(
+                        scene.background_color(&ray),
+                        ray.wavelength(),
+                    );
+            };
+
+            let Some((scattered, attenuation)) = ray_hit.material.scatter(
+                &ray,
+                ray_hit.point(),
+                ray_hit.normal(),
+                ray_hit.is_front_face(),
+            ) else {
+                return Color::new(0.0, 0.0, 0.0);
+            };
+
+            throughput = throughput
+                * spectrum::for_wavelength(attenuation, ray.wavelength());
+
+            if !ray_hit.material.is_diffuse() {
+                ray = scattered
+                    .with_wavelength(ray.wavelength())
+                    .with_time(ray.time());
+                continue;
+            }
+
+            let light_ray = Ray::new(ray_hit.point(), self.light_direction)
+                .with_time(ray.time());
+            let cos_theta =
+                f64::max(ray_hit.normal().dot(self.light_direction), 0.0);
+            let is_lit = cos_theta > 0.0
+                && scene
+                    .does_hit(&light_ray, &Interval::new(0.001, f64::INFINITY))
+                    .is_none();
+
+            let mut irradiance = Color::new(1.0, 1.0, 1.0) * self.ambient;
+            if is_lit {
+                irradiance += spectrum::for_wavelength(
+                    scene.background_color(&light_ray),
+                    ray.wavelength(),
+                ) * cos_theta;
+            }
+
+            return throughput * irradiance;
+        }
+
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    fn supports_spectral(&self) -> bool {
+        true
+    }
+}
@@ -1,28 +1,65 @@
+mod animation;
 mod camera;
+mod checkpoint;
 mod color;
-mod error;
+pub mod error;
+mod film;
+mod filter;
+mod integrator;
 mod material;
 mod math;
 mod object;
-#[cfg(not(target_arch = "wasm32"))]
-mod profiler;
+mod partial;
+mod picking;
+mod progress;
 mod random;
 mod ray_hit;
 mod raytracer;
 mod raytracer_context;
+mod scene;
+mod spectrum;
+mod stats;
+mod texture;
 mod utils;
 
-pub use camera::Camera;
+pub use animation::Interpolation;
+pub use camera::aperture::{ApertureMask, ApertureShape};
+pub use camera::physical::PhysicalCamera;
+pub use camera::{Camera, CameraProjection, Projection};
+pub use checkpoint::CheckpointSettings;
 pub use color::Color;
+pub use error::Error;
+pub use filter::FilterType;
+pub use integrator::{Integrator, IntegratorType};
+pub use math::interval::Interval;
+pub use math::ray::Ray;
+pub use math::transform::Transform;
 pub use math::vector3::Vec3f;
-pub use ray_hit::RayHitTest;
-pub use raytracer::{Raytracer, RenderOptions, image::*};
+pub use ray_hit::{HitInterval, RayHitTest};
+pub use raytracer::{
+    Raytracer, RenderOptions, RenderRegion, RenderedImage, RenderedPartial,
+    image::*,
+};
 
 pub use material::Material;
+pub use material::coated::CoatedMaterial;
+pub use material::conductor::{ConductorMaterial, ConductorPreset};
 pub use material::lambert::LambertMaterial;
 pub use material::metal::MetalMaterial;
-pub use material::transparent::TransparentMaterial;
+pub use material::mix::MixMaterial;
+pub use material::principled::PrincipledMaterial;
+pub use material::transparent::{Dispersion, TransparentMaterial};
+pub use object::csg::{Csg, CsgOperation};
+pub use object::group::Group;
+pub use object::instance::Instance;
+pub use object::motion::{Keyframe, Motion};
 pub use object::sphere::Sphere;
+pub use object::sphere_set::SphereSet;
+pub use partial::PartialBuffer;
+pub use picking::{PickedObject, pick_object};
+pub use progress::RenderProgress;
+pub use stats::RenderStats;
+pub use texture::{CheckerTexture, ConstantTexture, Texture};
 
 pub use image as image_rs;
 
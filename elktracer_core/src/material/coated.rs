@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use crate::{
+    color::Color,
+    math::{onb::Onb, ray::Ray, vector3::Vec3f},
+    random,
+};
+
+use super::{
+    Material,
+    microfacet::{GgxDistribution, fresnel_dielectric},
+};
+
+/// Thin dielectric coat layered over an arbitrary base material, like
+/// varnish over wood or lacquer over paint.
+///
+/// Light either reflects off the coat according to its Fresnel term or
+/// passes through to the base. Light scattered by the base is tinted on its
+/// way through the coat and leaves it with the coat's transmittance. The
+/// bending of directions inside the thin coat is ignored.
+pub struct CoatedMaterial {
+    base: Arc<dyn Material>,
+    refraction_index: f64,
+    tint: Color,
+    distribution: GgxDistribution,
+}
+
+impl CoatedMaterial {
+    pub fn new(
+        base: Arc<dyn Material>,
+        refraction_index: f64,
+        roughness: f64,
+        tint: Color,
+    ) -> Self {
+        Self {
+            base,
+            refraction_index,
+            tint,
+            distribution: GgxDistribution::from_roughness(roughness),
+        }
+    }
+
+    /// Tint after traversing the coat once in each direction. Equals `tint`
+    /// at normal incidence and gets stronger at grazing angles.
+    fn coat_transmittance(&self, cos_in: f64, cos_out: f64) -> Color {
+        let exponent = 0.5 * (1.0 / cos_in + 1.0 / cos_out);
+        Color::new(
+            f64::powf(self.tint.r(), exponent),
+            f64::powf(self.tint.g(), exponent),
+            f64::powf(self.tint.b(), exponent),
+        )
+    }
+}
+
+impl Material for CoatedMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_point: Vec3f,
+        hit_normal: Vec3f,
+        is_hit_front_face: bool,
+    ) -> Option<(Ray, Color)> {
+        if !is_hit_front_face {
+            return self.base.scatter(
+                ray,
+                hit_point,
+                hit_normal,
+                is_hit_front_face,
+            );
+        }
+
+        let onb = Onb::new(hit_normal);
+        let wo = onb.to_local(-ray.direction().unit());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let h = self.distribution.sample_visible_normal(wo);
+        let wo_dot_h = wo.dot(h);
+        if random::random_f64_0_1()
+            < fresnel_dielectric(wo_dot_h, self.refraction_index)
+        {
+            let wi = h * (2.0 * wo_dot_h) - wo;
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let weight =
+                self.distribution.g2(wo, wi) / self.distribution.g1(wo);
+            return Some((
+                Ray::new(hit_point, onb.to_world(wi)),
+                Color::new(weight, weight, weight),
+            ));
+        }
+
+        let (scattered, attenuation) =
+            self.base
+                .scatter(ray, hit_point, hit_normal, is_hit_front_face)?;
+
+        let cos_out = scattered.direction().unit().dot(hit_normal);
+        if cos_out <= 0.0 {
+            // Transmission through the base, the coat is not involved.
+            return Some((scattered, attenuation));
+        }
+
+        let exit_transmission =
+            1.0 - fresnel_dielectric(cos_out, self.refraction_index);
+
+        Some((
+            scattered,
+            attenuation
+                * self.coat_transmittance(wo.z(), cos_out)
+                * exit_transmission,
+        ))
+    }
+
+    fn is_diffuse(&self) -> bool {
+        self.base.is_diffuse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_approx_eq, material::lambert::LambertMaterial};
+
+    #[test]
+    fn coat_transmittance_should_equal_tint_at_normal_incidence() {
+        let material = CoatedMaterial::new(
+            Arc::new(LambertMaterial::new(Color::new(1.0, 1.0, 1.0))),
+            1.5,
+            0.0,
+            Color::new(0.9, 0.5, 0.1),
+        );
+        let transmittance = material.coat_transmittance(1.0, 1.0);
+
+        let epsilon = 1e-9;
+        assert_approx_eq!(transmittance.r(), 0.9, epsilon);
+        assert_approx_eq!(transmittance.g(), 0.5, epsilon);
+        assert_approx_eq!(transmittance.b(), 0.1, epsilon);
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    color::Color,
+    math::{onb::Onb, ray::Ray, vector3::Vec3f},
+};
+
+use super::{
+    Material,
+    microfacet::{GgxDistribution, fresnel_conductor},
+};
+
+/// Measured metals with their complex refraction index sampled at red,
+/// green and blue wavelengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConductorPreset {
+    Gold,
+    Copper,
+    Aluminium,
+    Silver,
+}
+
+impl ConductorPreset {
+    pub const ALL: [ConductorPreset; 4] = [
+        ConductorPreset::Gold,
+        ConductorPreset::Copper,
+        ConductorPreset::Aluminium,
+        ConductorPreset::Silver,
+    ];
+
+    pub fn eta(&self) -> [f64; 3] {
+        match self {
+            ConductorPreset::Gold => [0.143, 0.374, 1.442],
+            ConductorPreset::Copper => [0.200, 0.924, 1.102],
+            ConductorPreset::Aluminium => [1.657, 0.880, 0.521],
+            ConductorPreset::Silver => [0.155, 0.117, 0.138],
+        }
+    }
+
+    pub fn k(&self) -> [f64; 3] {
+        match self {
+            ConductorPreset::Gold => [3.983, 2.385, 1.603],
+            ConductorPreset::Copper => [3.912, 2.452, 2.142],
+            ConductorPreset::Aluminium => [9.224, 6.270, 4.837],
+            ConductorPreset::Silver => [4.828, 3.122, 2.147],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConductorPreset::Gold => "gold",
+            ConductorPreset::Copper => "copper",
+            ConductorPreset::Aluminium => "aluminium",
+            ConductorPreset::Silver => "silver",
+        }
+    }
+}
+
+impl fmt::Display for ConductorPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ConductorPreset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        ConductorPreset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == value)
+            .ok_or_else(|| format!("Unknown conductor preset '{}'", value))
+    }
+}
+
+/// Rough metal using a GGX microfacet distribution with visible normal
+/// sampling and the exact conductor Fresnel term. Unlike `MetalMaterial`
+/// the reflectance follows from the complex refraction index (`eta`, `k`)
+/// per color channel.
+pub struct ConductorMaterial {
+    eta: Color,
+    k: Color,
+    distribution: GgxDistribution,
+}
+
+impl ConductorMaterial {
+    pub fn new(eta: Color, k: Color, roughness: f64) -> Self {
+        Self {
+            eta,
+            k,
+            distribution: GgxDistribution::from_roughness(roughness),
+        }
+    }
+
+    pub fn from_preset(preset: ConductorPreset, roughness: f64) -> Self {
+        Self::new(
+            Color::from(preset.eta()),
+            Color::from(preset.k()),
+            roughness,
+        )
+    }
+
+    fn fresnel(&self, cos_theta: f64) -> Color {
+        Color::new(
+            fresnel_conductor(cos_theta, self.eta.r(), self.k.r()),
+            fresnel_conductor(cos_theta, self.eta.g(), self.k.g()),
+            fresnel_conductor(cos_theta, self.eta.b(), self.k.b()),
+        )
+    }
+}
+
+impl Material for ConductorMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_point: Vec3f,
+        hit_normal: Vec3f,
+        _is_hit_front_face: bool,
+    ) -> Option<(Ray, Color)> {
+        let onb = Onb::new(hit_normal);
+        let wo = onb.to_local(-ray.direction().unit());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let h = self.distribution.sample_visible_normal(wo);
+        let wo_dot_h = wo.dot(h);
+        let wi = h * (2.0 * wo_dot_h) - wo;
+        if wi.z() <= 0.0 {
+            return None;
+        }
+
+        let weight = self.distribution.g2(wo, wi) / self.distribution.g1(wo);
+
+        Some((
+            Ray::new(hit_point, onb.to_world(wi)),
+            self.fresnel(wo_dot_h) * weight,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_should_parse_all_presets() {
+        for preset in ConductorPreset::ALL {
+            assert_eq!(
+                ConductorPreset::from_str(&preset.to_string()),
+                Ok(preset)
+            );
+        }
+    }
+
+    #[test]
+    fn gold_should_reflect_more_red_than_blue() {
+        let gold = ConductorMaterial::from_preset(ConductorPreset::Gold, 0.0);
+        let fresnel = gold.fresnel(1.0);
+
+        assert!(fresnel.r() > fresnel.b());
+        assert!(fresnel.r() > 0.9);
+    }
+}
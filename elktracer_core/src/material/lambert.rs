@@ -36,4 +36,8 @@ impl Material for LambertMaterial {
 
         Some((scattered, self.albedo))
     }
+
+    fn is_diffuse(&self) -> bool {
+        true
+    }
 }
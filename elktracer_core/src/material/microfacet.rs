@@ -0,0 +1,186 @@
+use std::f64;
+
+use crate::{math::vector3::Vec3f, random};
+
+/// Trowbridge-Reitz (GGX) microfacet distribution. All directions are given
+/// in the local shading frame, i.e. the macro surface normal is +Z.
+#[derive(Debug, Clone, Copy)]
+pub struct GgxDistribution {
+    alpha: f64,
+}
+
+impl GgxDistribution {
+    /// Uses the perceptual mapping `alpha = roughness²`.
+    pub fn from_roughness(roughness: f64) -> Self {
+        let roughness = f64::clamp(roughness, 0.0, 1.0);
+        Self {
+            alpha: f64::max(roughness * roughness, 1e-4),
+        }
+    }
+
+    fn lambda(&self, v: Vec3f) -> f64 {
+        let cos2_theta = v.z() * v.z();
+        if cos2_theta <= 0.0 {
+            return f64::INFINITY;
+        }
+        let tan2_theta = f64::max(1.0 - cos2_theta, 0.0) / cos2_theta;
+        (-1.0 + f64::sqrt(1.0 + self.alpha * self.alpha * tan2_theta)) * 0.5
+    }
+
+    /// Smith masking term for a single direction.
+    pub fn g1(&self, v: Vec3f) -> f64 {
+        1.0 / (1.0 + self.lambda(v))
+    }
+
+    /// Height correlated Smith masking-shadowing term.
+    pub fn g2(&self, wo: Vec3f, wi: Vec3f) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Samples a microfacet normal from the distribution of normals visible
+    /// from `wo` (Heitz 2018). Reflecting or refracting about it and
+    /// weighting by `g2 / g1` gives an estimator without the D term.
+    pub fn sample_visible_normal(&self, wo: Vec3f) -> Vec3f {
+        let wo = if wo.z() < 0.0 { -wo } else { wo };
+        let vh =
+            Vec3f::new(self.alpha * wo.x(), self.alpha * wo.y(), wo.z()).unit();
+
+        let length_squared = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if length_squared > 0.0 {
+            Vec3f::new(-vh.y(), vh.x(), 0.0) / f64::sqrt(length_squared)
+        } else {
+            Vec3f::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(t1);
+
+        let r = f64::sqrt(random::random_f64_0_1());
+        let phi = 2.0 * f64::consts::PI * random::random_f64_0_1();
+        let p1 = r * f64::cos(phi);
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * f64::sqrt(f64::max(1.0 - p1 * p1, 0.0))
+            + s * r * f64::sin(phi);
+
+        let nh = t1 * p1
+            + t2 * p2
+            + vh * f64::sqrt(f64::max(1.0 - p1 * p1 - p2 * p2, 0.0));
+
+        Vec3f::new(
+            self.alpha * nh.x(),
+            self.alpha * nh.y(),
+            f64::max(nh.z(), 1e-6),
+        )
+        .unit()
+    }
+}
+
+/// Schlick's `(1 - cos)^5` Fresnel weight.
+pub fn schlick_weight(cosine: f64) -> f64 {
+    f64::powi(f64::clamp(1.0 - cosine, 0.0, 1.0), 5)
+}
+
+/// Unpolarized Fresnel reflectance of a dielectric interface. `eta` is the
+/// ratio of the refraction index on the transmitted side to the incident
+/// side.
+pub fn fresnel_dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = f64::clamp(cos_theta_i, 0.0, 1.0);
+    let sin2_theta_t = (1.0 - cos_theta_i * cos_theta_i) / (eta * eta);
+    if sin2_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = f64::sqrt(1.0 - sin2_theta_t);
+
+    let r_parallel =
+        (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let r_perpendicular =
+        (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+
+    (r_parallel * r_parallel + r_perpendicular * r_perpendicular) * 0.5
+}
+
+/// Unpolarized Fresnel reflectance of a conductor with the complex
+/// refraction index `eta + i·k`, seen from a medium with index 1.
+pub fn fresnel_conductor(cos_theta_i: f64, eta: f64, k: f64) -> f64 {
+    let cos2_theta = f64::clamp(cos_theta_i * cos_theta_i, 0.0, 1.0);
+    let sin2_theta = 1.0 - cos2_theta;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2_theta;
+    let a2_plus_b2 = f64::sqrt(t0 * t0 + 4.0 * eta2 * k2);
+    let t1 = a2_plus_b2 + cos2_theta;
+    let a = f64::sqrt(f64::max(0.5 * (a2_plus_b2 + t0), 0.0));
+    let t2 = 2.0 * f64::sqrt(cos2_theta) * a;
+    let r_perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2_theta * a2_plus_b2 + sin2_theta * sin2_theta;
+    let t4 = t2 * sin2_theta;
+    let r_parallel = r_perpendicular * (t3 - t4) / (t3 + t4);
+
+    (r_parallel + r_perpendicular) * 0.5
+}
+
+/// Samples a cosine weighted direction around +Z.
+pub fn sample_cosine_hemisphere() -> Vec3f {
+    let r = f64::sqrt(random::random_f64_0_1());
+    let phi = 2.0 * f64::consts::PI * random::random_f64_0_1();
+    let x = r * f64::cos(phi);
+    let y = r * f64::sin(phi);
+
+    Vec3f::new(x, y, f64::sqrt(f64::max(1.0 - x * x - y * y, 0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_approx_eq, math::vector3::TEST_EPSILON};
+
+    #[test]
+    fn fresnel_dielectric_should_match_normal_incidence() {
+        // ((1 - 1.5) / (1 + 1.5))² = 0.04
+        assert_approx_eq!(fresnel_dielectric(1.0, 1.5), 0.04, 1e-9);
+    }
+
+    #[test]
+    fn fresnel_dielectric_should_report_total_internal_reflection() {
+        assert_eq!(fresnel_dielectric(0.1, 1.0 / 1.5), 1.0);
+    }
+
+    #[test]
+    fn fresnel_conductor_should_match_normal_incidence() {
+        // ((eta - 1)² + k²) / ((eta + 1)² + k²)
+        let expected = (0.25 + 9.0) / (6.25 + 9.0);
+        assert_approx_eq!(fresnel_conductor(1.0, 1.5, 3.0), expected, 1e-9);
+    }
+
+    #[test]
+    fn fresnel_conductor_should_reduce_to_dielectric_without_absorption() {
+        for cosine in [0.2, 0.5, 0.9] {
+            assert_approx_eq!(
+                fresnel_conductor(cosine, 1.5, 0.0),
+                fresnel_dielectric(cosine, 1.5),
+                1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn sample_visible_normal_should_face_up() {
+        let distribution = GgxDistribution::from_roughness(0.8);
+        let wo = Vec3f::new(0.6, 0.0, 0.8);
+        for _ in 0..100 {
+            let h = distribution.sample_visible_normal(wo);
+            assert!(h.z() > 0.0);
+            assert_approx_eq!(h.magnitude(), 1.0, TEST_EPSILON);
+        }
+    }
+
+    #[test]
+    fn g1_should_be_one_at_normal_incidence() {
+        let distribution = GgxDistribution::from_roughness(0.5);
+        assert_approx_eq!(
+            distribution.g1(Vec3f::new(0.0, 0.0, 1.0)),
+            1.0,
+            1e-9
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use crate::{
+    color::Color,
+    math::{ray::Ray, vector3::Vec3f},
+    random,
+    texture::Texture,
+};
+
+use super::Material;
+
+/// Blends two materials by picking one of them per scatter event. A weight
+/// of 0 yields only `first`, a weight of 1 only `second`.
+pub struct MixMaterial {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    weight: Arc<dyn Texture>,
+}
+
+impl MixMaterial {
+    pub fn new(
+        first: Arc<dyn Material>,
+        second: Arc<dyn Material>,
+        weight: Arc<dyn Texture>,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            weight,
+        }
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_point: Vec3f,
+        hit_normal: Vec3f,
+        is_hit_front_face: bool,
+    ) -> Option<(Ray, Color)> {
+        let weight = f64::clamp(self.weight.value(hit_point), 0.0, 1.0);
+        let material = if random::random_f64_0_1() < weight {
+            &self.second
+        } else {
+            &self.first
+        };
+
+        material.scatter(ray, hit_point, hit_normal, is_hit_front_face)
+    }
+
+    fn is_diffuse(&self) -> bool {
+        self.first.is_diffuse() && self.second.is_diffuse()
+    }
+}
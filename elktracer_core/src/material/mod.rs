@@ -1,5 +1,10 @@
+pub mod coated;
+pub mod conductor;
 pub mod lambert;
 pub mod metal;
+pub mod microfacet;
+pub mod mix;
+pub mod principled;
 pub mod transparent;
 
 use crate::{
@@ -7,7 +12,7 @@ use crate::{
     math::{ray::Ray, vector3::Vec3f},
 };
 
-pub trait Material {
+pub trait Material: Send + Sync {
     fn scatter(
         &self,
         ray: &Ray,
@@ -15,4 +20,10 @@ pub trait Material {
         hit_normal: Vec3f,
         is_hit_front_face: bool,
     ) -> Option<(Ray, Color)>;
+
+    /// Whether the material scatters light diffusely. Integrators that only
+    /// follow specular paths shade diffuse surfaces with direct lighting.
+    fn is_diffuse(&self) -> bool {
+        false
+    }
 }
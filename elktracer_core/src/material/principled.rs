@@ -0,0 +1,209 @@
+use crate::{
+    color::Color,
+    math::{onb::Onb, ray::Ray, vector3::Vec3f},
+    random,
+};
+
+use super::{
+    Material,
+    microfacet::{
+        GgxDistribution, fresnel_dielectric, sample_cosine_hemisphere,
+        schlick_weight,
+    },
+};
+
+/// Roughness of the clear coat layer, which is always fairly glossy.
+const CLEARCOAT_ROUGHNESS: f64 = 0.1;
+/// Refraction index of the clear coat layer.
+const CLEARCOAT_REFRACTION_INDEX: f64 = 1.5;
+
+/// Disney-style principled BSDF combining a clear coat layer, a GGX
+/// specular lobe, a metallic lobe, rough transmission and a diffuse lobe
+/// with sheen. One lobe is picked stochastically per scatter event.
+///
+/// All parameters are in 0..1. `specular` maps to a normal incidence
+/// reflectance of `0.08 * specular`, so the default of 0.5 equals an
+/// refraction index of 1.5.
+pub struct PrincipledMaterial {
+    base_color: Color,
+    metallic: f64,
+    specular: f64,
+    clearcoat: f64,
+    sheen: f64,
+    transmission: f64,
+    refraction_index: f64,
+    specular_distribution: GgxDistribution,
+    clearcoat_distribution: GgxDistribution,
+}
+
+impl PrincipledMaterial {
+    pub fn new(
+        base_color: Color,
+        metallic: f64,
+        roughness: f64,
+        specular: f64,
+        clearcoat: f64,
+        sheen: f64,
+        transmission: f64,
+    ) -> Self {
+        let specular = f64::clamp(specular, 0.0, 1.0);
+        Self {
+            base_color,
+            metallic: f64::clamp(metallic, 0.0, 1.0),
+            specular,
+            clearcoat: f64::clamp(clearcoat, 0.0, 1.0),
+            sheen: f64::clamp(sheen, 0.0, 1.0),
+            transmission: f64::clamp(transmission, 0.0, 1.0),
+            refraction_index: Self::specular_to_refraction_index(specular),
+            specular_distribution: GgxDistribution::from_roughness(roughness),
+            clearcoat_distribution: GgxDistribution::from_roughness(
+                CLEARCOAT_ROUGHNESS,
+            ),
+        }
+    }
+
+    fn specular_to_refraction_index(specular: f64) -> f64 {
+        let r0_sqrt = f64::sqrt(0.08 * specular);
+        (1.0 + r0_sqrt) / (1.0 - r0_sqrt)
+    }
+
+    fn reflect(wo: Vec3f, microfacet_normal: Vec3f) -> Vec3f {
+        microfacet_normal * (2.0 * wo.dot(microfacet_normal)) - wo
+    }
+
+    fn scatter_local(
+        &self,
+        wo: Vec3f,
+        is_hit_front_face: bool,
+    ) -> Option<(Vec3f, Color)> {
+        // Clear coat sits on top of everything else and reflects white.
+        if is_hit_front_face && self.clearcoat > 0.0 {
+            let coat_probability = self.clearcoat
+                * fresnel_dielectric(wo.z(), CLEARCOAT_REFRACTION_INDEX);
+            if random::random_f64_0_1() < coat_probability {
+                let distribution = &self.clearcoat_distribution;
+                let h = distribution.sample_visible_normal(wo);
+                let wi = Self::reflect(wo, h);
+                let weight = distribution.g2(wo, wi) / distribution.g1(wo);
+                return (wi.z() > 0.0)
+                    .then_some((wi, Color::new(weight, weight, weight)));
+            }
+        }
+
+        let distribution = &self.specular_distribution;
+        let h = distribution.sample_visible_normal(wo);
+        let wo_dot_h = wo.dot(h);
+
+        if random::random_f64_0_1() < self.metallic {
+            let wi = Self::reflect(wo, h);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            let fresnel_weight = schlick_weight(wo_dot_h);
+            let fresnel = self.base_color * (1.0 - fresnel_weight)
+                + Color::new(fresnel_weight, fresnel_weight, fresnel_weight);
+            let weight = distribution.g2(wo, wi) / distribution.g1(wo);
+            return Some((wi, fresnel * weight));
+        }
+
+        let eta = if is_hit_front_face {
+            self.refraction_index
+        } else {
+            1.0 / self.refraction_index
+        };
+
+        if self.specular > 0.0
+            && random::random_f64_0_1() < fresnel_dielectric(wo_dot_h, eta)
+        {
+            let wi = Self::reflect(wo, h);
+            let weight = distribution.g2(wo, wi) / distribution.g1(wo);
+            return (wi.z() > 0.0)
+                .then_some((wi, Color::new(weight, weight, weight)));
+        }
+
+        // Once inside the medium, the only way out is transmission.
+        let transmission = if is_hit_front_face {
+            self.transmission
+        } else {
+            1.0
+        };
+        if random::random_f64_0_1() < transmission {
+            let wi = (-wo).refract(h, 1.0 / eta).unit();
+            if wi.z() >= 0.0 {
+                return None;
+            }
+            let weight = distribution.g2(wo, wi) / distribution.g1(wo);
+            return Some((wi, self.base_color * weight));
+        }
+
+        let wi = sample_cosine_hemisphere();
+        let half_vector = (wi + wo).unit();
+        let sheen = self.sheen * schlick_weight(wi.dot(half_vector));
+
+        Some((wi, self.base_color + Color::new(sheen, sheen, sheen)))
+    }
+}
+
+impl Material for PrincipledMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit_point: Vec3f,
+        hit_normal: Vec3f,
+        is_hit_front_face: bool,
+    ) -> Option<(Ray, Color)> {
+        let onb = Onb::new(hit_normal);
+        let wo = onb.to_local(-ray.direction().unit());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        self.scatter_local(wo, is_hit_front_face)
+            .map(|(wi, attenuation)| {
+                (Ray::new(hit_point, onb.to_world(wi)), attenuation)
+            })
+    }
+
+    fn is_diffuse(&self) -> bool {
+        self.metallic < 0.5 && self.transmission < 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn specular_to_refraction_index_should_be_correct() {
+        assert_approx_eq!(
+            PrincipledMaterial::specular_to_refraction_index(0.5),
+            1.5,
+            1e-9
+        );
+        assert_approx_eq!(
+            PrincipledMaterial::specular_to_refraction_index(0.0),
+            1.0,
+            1e-9
+        );
+    }
+
+    #[test]
+    fn scatter_should_stay_above_surface_for_opaque_material() {
+        let material = PrincipledMaterial::new(
+            Color::new(0.8, 0.2, 0.2),
+            0.3,
+            0.4,
+            0.5,
+            0.5,
+            0.2,
+            0.0,
+        );
+        let wo = Vec3f::new(0.3, 0.2, 0.9).unit();
+        for _ in 0..200 {
+            if let Some((wi, _)) = material.scatter_local(wo, true) {
+                assert!(wi.z() > 0.0);
+            }
+        }
+    }
+}
@@ -1,14 +1,98 @@
-use crate::{color::Color, math::ray::Ray, random};
+use crate::{
+    color::Color,
+    math::{onb::Onb, ray::Ray, vector3::Vec3f},
+    random,
+};
 
-use super::Material;
+use super::{
+    Material,
+    microfacet::{GgxDistribution, fresnel_dielectric},
+};
+
+/// Wavelength dependent refraction index. Wavelengths are in µm and the
+/// coefficients use the units common in optical glass catalogs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dispersion {
+    /// `n(λ) = a + b / λ²`
+    Cauchy { a: f64, b: f64 },
+    /// `n²(λ) = 1 + Σ bᵢ λ² / (λ² - cᵢ)`
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Schott N-BK7 crown glass.
+    pub const BK7_CAUCHY: Dispersion = Dispersion::Cauchy {
+        a: 1.5046,
+        b: 0.00420,
+    };
+    pub const BK7_SELLMEIER: Dispersion = Dispersion::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+
+    pub fn refraction_index(&self, wavelength_nm: f64) -> f64 {
+        let wavelength = wavelength_nm * 1e-3;
+        let wavelength2 = wavelength * wavelength;
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / wavelength2,
+            Dispersion::Sellmeier { b, c } => f64::sqrt(
+                1.0 + (0..3)
+                    .map(|i| b[i] * wavelength2 / (wavelength2 - c[i]))
+                    .sum::<f64>(),
+            ),
+        }
+    }
+}
 
 pub struct TransparentMaterial {
     refraction_index: f64,
+    /// Only used for rays carrying a wavelength.
+    dispersion: Option<Dispersion>,
+    /// Per channel absorption coefficient of the medium.
+    absorption: Color,
+    /// `None` for perfectly smooth glass.
+    distribution: Option<GgxDistribution>,
 }
 
 impl TransparentMaterial {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self {
+            refraction_index,
+            dispersion: None,
+            absorption: Color::new(0.0, 0.0, 0.0),
+            distribution: None,
+        }
+    }
+
+    /// Tints light traveling through the medium following the Beer-Lambert
+    /// law. After a distance of `1 / density` the transmitted light equals
+    /// `absorption_color`.
+    pub fn with_absorption(
+        mut self,
+        absorption_color: Color,
+        density: f64,
+    ) -> Self {
+        let coefficient =
+            |channel: f64| -f64::ln(f64::clamp(channel, 1e-6, 1.0)) * density;
+        self.absorption = Color::new(
+            coefficient(absorption_color.r()),
+            coefficient(absorption_color.g()),
+            coefficient(absorption_color.b()),
+        );
+        self
+    }
+
+    /// Replaces the constant refraction index in spectral mode.
+    pub fn with_dispersion(mut self, dispersion: Option<Dispersion>) -> Self {
+        self.dispersion = dispersion;
+        self
+    }
+
+    /// Frosts the surface by refracting through GGX microfacets.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.distribution = (roughness > 0.0)
+            .then(|| GgxDistribution::from_roughness(roughness));
+        self
     }
 
     fn get_reflectance(&self, cosine: f64, refraction_index: f64) -> f64 {
@@ -17,6 +101,73 @@ impl TransparentMaterial {
 
         r0_2 + (1.0 - r0_2) * f64::powi(1.0 - cosine, 5)
     }
+
+    fn transmittance(&self, distance: f64) -> Color {
+        Color::new(
+            f64::exp(-self.absorption.r() * distance),
+            f64::exp(-self.absorption.g() * distance),
+            f64::exp(-self.absorption.b() * distance),
+        )
+    }
+
+    fn scatter_smooth(
+        &self,
+        unit_direction: Vec3f,
+        hit_normal: Vec3f,
+        ri: f64,
+    ) -> Option<(Vec3f, f64)> {
+        let cos_theta = f64::min(-unit_direction.dot(hit_normal), 1.0);
+        let sin_theta = f64::sqrt(1.0 - cos_theta * cos_theta);
+
+        let cannot_refract = ri * sin_theta > 1.0;
+
+        let direction = if cannot_refract
+            || self.get_reflectance(cos_theta, ri) > random::random_f64_0_1()
+        {
+            unit_direction.reflect(hit_normal)
+        } else {
+            unit_direction.refract(hit_normal, ri)
+        };
+
+        Some((direction, 1.0))
+    }
+
+    fn scatter_rough(
+        distribution: &GgxDistribution,
+        unit_direction: Vec3f,
+        hit_normal: Vec3f,
+        ri: f64,
+    ) -> Option<(Vec3f, f64)> {
+        let onb = Onb::new(hit_normal);
+        let wo = onb.to_local(-unit_direction);
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let h = distribution.sample_visible_normal(wo);
+        let wo_dot_h = wo.dot(h);
+
+        let wi = if fresnel_dielectric(wo_dot_h, 1.0 / ri)
+            > random::random_f64_0_1()
+        {
+            let reflected = h * (2.0 * wo_dot_h) - wo;
+            if reflected.z() <= 0.0 {
+                return None;
+            }
+            reflected
+        } else {
+            let refracted = (-wo).refract(h, ri);
+            if refracted.z() >= 0.0 {
+                return None;
+            }
+            refracted
+        };
+
+        Some((
+            onb.to_world(wi),
+            distribution.g2(wo, wi) / distribution.g1(wo),
+        ))
+    }
 }
 
 impl Material for TransparentMaterial {
@@ -29,26 +180,87 @@ impl Material for TransparentMaterial {
     ) -> Option<(Ray, Color)> {
         let unit_direction = ray.direction().unit();
 
+        let refraction_index = match (self.dispersion, ray.wavelength()) {
+            (Some(dispersion), Some(wavelength)) => {
+                dispersion.refraction_index(wavelength)
+            }
+            _ => self.refraction_index,
+        };
         let ri = if is_hit_front_face {
-            1.0 / self.refraction_index
+            1.0 / refraction_index
         } else {
-            self.refraction_index
+            refraction_index
         };
-        let cos_theta = f64::min(-unit_direction.dot(hit_normal), 1.0);
-        let sin_theta = f64::sqrt(1.0 - cos_theta * cos_theta);
 
-        let cannot_refract = ri * sin_theta > 1.0;
+        let (direction, weight) = match &self.distribution {
+            Some(distribution) => Self::scatter_rough(
+                distribution,
+                unit_direction,
+                hit_normal,
+                ri,
+            )?,
+            None => self.scatter_smooth(unit_direction, hit_normal, ri)?,
+        };
 
-        let direction = if cannot_refract
-            || self.get_reflectance(cos_theta, ri) > random::random_f64_0_1()
-        {
-            unit_direction.reflect(hit_normal)
+        let scattered = Ray::new(hit_point, direction);
+
+        // Hitting a back face means the ray traveled through the medium.
+        let attenuation = if is_hit_front_face {
+            Color::new(1.0, 1.0, 1.0)
         } else {
-            unit_direction.refract(hit_normal, ri)
+            self.transmittance((hit_point - ray.origin()).magnitude())
         };
 
-        let scattered = Ray::new(hit_point, direction);
+        Some((scattered, attenuation * weight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn transmittance_should_match_absorption_color_at_unit_depth() {
+        let density = 4.0;
+        let material = TransparentMaterial::new(1.5)
+            .with_absorption(Color::new(0.8, 0.5, 0.2), density);
+        let transmittance = material.transmittance(1.0 / density);
+
+        let epsilon = 1e-9;
+        assert_approx_eq!(transmittance.r(), 0.8, epsilon);
+        assert_approx_eq!(transmittance.g(), 0.5, epsilon);
+        assert_approx_eq!(transmittance.b(), 0.2, epsilon);
+    }
+
+    #[test]
+    fn dispersion_should_match_bk7_at_helium_d_line() {
+        let epsilon = 1e-3;
+        assert_approx_eq!(
+            Dispersion::BK7_SELLMEIER.refraction_index(587.56),
+            1.5168,
+            epsilon
+        );
+        assert_approx_eq!(
+            Dispersion::BK7_CAUCHY.refraction_index(587.56),
+            1.5168,
+            epsilon
+        );
+    }
+
+    #[test]
+    fn dispersion_should_refract_blue_stronger_than_red() {
+        let dispersion = Dispersion::BK7_SELLMEIER;
+        assert!(
+            dispersion.refraction_index(450.0)
+                > dispersion.refraction_index(650.0)
+        );
+    }
+
+    #[test]
+    fn transmittance_should_be_white_without_absorption() {
+        let transmittance = TransparentMaterial::new(1.5).transmittance(10.0);
 
-        Some((scattered, Color::new(1.0, 1.0, 1.0)))
+        assert_eq!(transmittance, Color::new(1.0, 1.0, 1.0));
     }
 }
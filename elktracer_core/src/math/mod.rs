@@ -1,3 +1,6 @@
 pub mod interval;
+pub mod onb;
 pub mod ray;
-pub mod vector3;
\ No newline at end of file
+pub mod simd;
+pub mod transform;
+pub mod vector3;
@@ -0,0 +1,62 @@
+use super::vector3::Vec3f;
+
+/// Orthonormal basis with `w` along a given normal. Used to move directions
+/// into a local shading frame where the normal is the Z axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Onb {
+    u: Vec3f,
+    v: Vec3f,
+    w: Vec3f,
+}
+
+impl Onb {
+    pub fn new(normal: Vec3f) -> Self {
+        let w = normal.unit();
+        let a = if f64::abs(w.x()) > 0.9 {
+            Vec3f::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3f::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(a).unit();
+        let u = w.cross(v);
+
+        Self { u, v, w }
+    }
+
+    pub fn to_local(self, vector: Vec3f) -> Vec3f {
+        Vec3f::new(vector.dot(self.u), vector.dot(self.v), vector.dot(self.w))
+    }
+
+    pub fn to_world(self, vector: Vec3f) -> Vec3f {
+        self.u * vector.x() + self.v * vector.y() + self.w * vector.z()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_approx_eq, math::vector3::TEST_EPSILON};
+
+    #[test]
+    fn to_local_should_map_normal_to_z() {
+        let normal = Vec3f::new(1.0, 2.0, -2.0).unit();
+        let local = Onb::new(normal).to_local(normal);
+
+        let epsilon = 1e-9;
+        assert_approx_eq!(local.x(), 0.0, epsilon);
+        assert_approx_eq!(local.y(), 0.0, epsilon);
+        assert_approx_eq!(local.z(), 1.0, epsilon);
+    }
+
+    #[test]
+    fn to_world_should_invert_to_local() {
+        let onb = Onb::new(Vec3f::new(0.95, 0.1, 0.3));
+        let vector = Vec3f::new(0.3, -0.7, 0.2);
+        let actual = onb.to_world(onb.to_local(vector));
+
+        let epsilon = TEST_EPSILON;
+        assert_approx_eq!(actual.x(), vector.x(), epsilon);
+        assert_approx_eq!(actual.y(), vector.y(), epsilon);
+        assert_approx_eq!(actual.z(), vector.z(), epsilon);
+    }
+}
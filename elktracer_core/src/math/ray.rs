@@ -4,11 +4,30 @@ use super::vector3::Vec3f;
 pub struct Ray {
     origin: Vec3f,
     direction: Vec3f,
+    /// Wavelength in nm when tracing in spectral mode.
+    wavelength: Option<f64>,
+    /// Point in time within the camera shutter interval, in seconds.
+    time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Vec3f, direction: Vec3f) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            wavelength: None,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
     }
 
     pub fn at(&self, t: f64) -> Vec3f {
@@ -22,6 +41,14 @@ impl Ray {
     pub fn direction(&self) -> Vec3f {
         self.direction
     }
+
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelength
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
 }
 
 #[cfg(test)]
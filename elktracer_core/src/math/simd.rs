@@ -0,0 +1,116 @@
+use std::ops::Sub;
+
+use wide::{CmpGe, CmpGt, CmpLt};
+
+use super::vector3::{Float, Vec3f};
+
+/// SIMD register of vector components, four lanes of `f64` or eight lanes
+/// of `f32` with the `f32` feature.
+#[cfg(feature = "f32")]
+pub type Lanes = wide::f32x8;
+#[cfg(not(feature = "f32"))]
+pub type Lanes = wide::f64x4;
+
+pub const LANE_COUNT: usize = size_of::<Lanes>() / size_of::<Float>();
+
+/// Up to `LANE_COUNT` vectors in structure of arrays layout, so that an
+/// operation is applied to all of them at once.
+#[derive(Clone, Copy)]
+pub struct Vec3Lanes {
+    x: Lanes,
+    y: Lanes,
+    z: Lanes,
+}
+
+impl Vec3Lanes {
+    /// Puts the vector into every lane.
+    pub fn splat(vector: Vec3f) -> Self {
+        let [x, y, z] = vector.components();
+        Self {
+            x: Lanes::splat(x),
+            y: Lanes::splat(y),
+            z: Lanes::splat(z),
+        }
+    }
+
+    /// Loads at most `LANE_COUNT` vectors. Unused lanes are NaN, which fails
+    /// every comparison.
+    pub fn from_slice(vectors: &[Vec3f]) -> Self {
+        assert!(vectors.len() <= LANE_COUNT);
+        let mut components = [[Float::NAN; LANE_COUNT]; 3];
+        for (lane, vector) in vectors.iter().enumerate() {
+            for (axis, component) in vector.components().into_iter().enumerate()
+            {
+                components[axis][lane] = component;
+            }
+        }
+        let [x, y, z] = components;
+        Self {
+            x: Lanes::new(x),
+            y: Lanes::new(y),
+            z: Lanes::new(z),
+        }
+    }
+
+    #[inline]
+    pub fn dot(&self, other: &Vec3Lanes) -> Lanes {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
+
+impl Sub for Vec3Lanes {
+    type Output = Vec3Lanes;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+/// Lanes of `values` strictly between `min` and `max`, and infinity in all
+/// other lanes, including NaN ones.
+#[inline]
+pub fn select_between(values: Lanes, min: Lanes, max: Lanes) -> Lanes {
+    (values.cmp_gt(min) & values.cmp_lt(max))
+        .blend(values, Lanes::splat(Float::INFINITY))
+}
+
+/// Whether any lane is zero or positive.
+#[inline]
+pub fn any_non_negative(values: Lanes) -> bool {
+    values.cmp_ge(Lanes::splat(0.0)).any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_slice_should_fill_unused_lanes_with_nan() {
+        let vectors = Vec3Lanes::from_slice(&[Vec3f::new(1.0, 2.0, 3.0)]);
+        let dot = vectors.dot(&Vec3Lanes::splat(Vec3f::one())).to_array();
+
+        assert_eq!(dot[0], 6.0);
+        assert!(dot[1..].iter().all(|value| value.is_nan()));
+    }
+
+    #[test]
+    fn select_between_should_exclude_bounds_and_nan() {
+        let mut values = [Float::NAN; LANE_COUNT];
+        values[..3].copy_from_slice(&[0.0, 0.5, 1.0]);
+        let selected = select_between(
+            Lanes::new(values),
+            Lanes::splat(0.0),
+            Lanes::splat(1.0),
+        )
+        .to_array();
+
+        assert_eq!(selected[0], Float::INFINITY);
+        assert_eq!(selected[1], 0.5);
+        assert!(selected[2..].iter().all(|value| value.is_infinite()));
+    }
+}
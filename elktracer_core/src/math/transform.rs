@@ -0,0 +1,253 @@
+use super::{ray::Ray, vector3::Vec3f};
+
+/// Rows of an affine 3x4 matrix whose last column is the translation.
+type Matrix = [[f64; 4]; 3];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+];
+
+/// Affine transform from object space to world space, stored together with
+/// its inverse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    matrix: Matrix,
+    inverse: Matrix,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            matrix: IDENTITY,
+            inverse: IDENTITY,
+        }
+    }
+
+    /// Scales, then rotates around the x, y and z axes by the angles in
+    /// degrees and finally translates. Scale components must not be zero.
+    pub fn new(
+        translation: Vec3f,
+        rotation_degrees: Vec3f,
+        scale: Vec3f,
+    ) -> Self {
+        let (sin_x, cos_x) = rotation_degrees.x().to_radians().sin_cos();
+        let (sin_y, cos_y) = rotation_degrees.y().to_radians().sin_cos();
+        let (sin_z, cos_z) = rotation_degrees.z().to_radians().sin_cos();
+        let rotation_x = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cos_x, -sin_x, 0.0],
+            [0.0, sin_x, cos_x, 0.0],
+        ];
+        let rotation_y = [
+            [cos_y, 0.0, sin_y, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-sin_y, 0.0, cos_y, 0.0],
+        ];
+        let rotation_z = [
+            [cos_z, -sin_z, 0.0, 0.0],
+            [sin_z, cos_z, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ];
+        let scaling = [
+            [scale.x(), 0.0, 0.0, 0.0],
+            [0.0, scale.y(), 0.0, 0.0],
+            [0.0, 0.0, scale.z(), 0.0],
+        ];
+
+        let mut matrix = multiply(
+            &rotation_z,
+            &multiply(&rotation_y, &multiply(&rotation_x, &scaling)),
+        );
+        matrix[0][3] = translation.x();
+        matrix[1][3] = translation.y();
+        matrix[2][3] = translation.z();
+
+        Self {
+            matrix,
+            inverse: invert(&matrix),
+        }
+    }
+
+    /// Applies this transform and then `outer`, e.g. the transform of the
+    /// group containing an object.
+    pub fn then(&self, outer: &Transform) -> Self {
+        Self {
+            matrix: multiply(&outer.matrix, &self.matrix),
+            inverse: multiply(&self.inverse, &outer.inverse),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            matrix: self.inverse,
+            inverse: self.matrix,
+        }
+    }
+
+    pub fn point(&self, point: Vec3f) -> Vec3f {
+        transform_point(&self.matrix, point)
+    }
+
+    pub fn vector(&self, vector: Vec3f) -> Vec3f {
+        transform_vector(&self.matrix, vector)
+    }
+
+    /// Transforms a surface normal with the inverse transpose, so that it
+    /// stays perpendicular to non-uniformly scaled surfaces. The result is
+    /// not normalized.
+    pub fn normal(&self, normal: Vec3f) -> Vec3f {
+        let m = &self.inverse;
+        Vec3f::new(
+            m[0][0] * normal.x() + m[1][0] * normal.y() + m[2][0] * normal.z(),
+            m[0][1] * normal.x() + m[1][1] * normal.y() + m[2][1] * normal.z(),
+            m[0][2] * normal.x() + m[1][2] * normal.y() + m[2][2] * normal.z(),
+        )
+    }
+
+    /// Maps a world space ray into object space. Directions are not
+    /// normalized, so distances along the ray stay the same.
+    pub fn inverse_ray(&self, ray: &Ray) -> Ray {
+        Ray::new(
+            transform_point(&self.inverse, ray.origin()),
+            transform_vector(&self.inverse, ray.direction()),
+        )
+        .with_wavelength(ray.wavelength())
+        .with_time(ray.time())
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+fn transform_point(m: &Matrix, point: Vec3f) -> Vec3f {
+    transform_vector(m, point) + Vec3f::new(m[0][3], m[1][3], m[2][3])
+}
+
+fn transform_vector(m: &Matrix, vector: Vec3f) -> Vec3f {
+    Vec3f::new(
+        m[0][0] * vector.x() + m[0][1] * vector.y() + m[0][2] * vector.z(),
+        m[1][0] * vector.x() + m[1][1] * vector.y() + m[1][2] * vector.z(),
+        m[2][0] * vector.x() + m[2][1] * vector.y() + m[2][2] * vector.z(),
+    )
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut product = [[0.0; 4]; 3];
+    for row in 0..3 {
+        for column in 0..4 {
+            product[row][column] = (0..3)
+                .map(|index| a[row][index] * b[index][column])
+                .sum::<f64>();
+        }
+        product[row][3] += a[row][3];
+    }
+    product
+}
+
+/// Inverts the linear part with its adjugate and undoes the translation.
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |row: usize, column: usize| {
+        let (r0, r1) = ((row + 1) % 3, (row + 2) % 3);
+        let (c0, c1) = ((column + 1) % 3, (column + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant = (0..3).map(|column| m[0][column] * cofactor(0, column));
+    let inverse_determinant = 1.0 / determinant.sum::<f64>();
+
+    let mut inverse = [[0.0; 4]; 3];
+    for (row, inverse_row) in inverse.iter_mut().enumerate() {
+        for (column, value) in inverse_row.iter_mut().take(3).enumerate() {
+            *value = cofactor(column, row) * inverse_determinant;
+        }
+        inverse_row[3] = -(0..3)
+            .map(|index| inverse_row[index] * m[index][3])
+            .sum::<f64>();
+    }
+    inverse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_approx_eq, math::vector3::TEST_EPSILON};
+
+    fn assert_vector_eq(actual: Vec3f, expected: Vec3f) {
+        assert_approx_eq!(actual.x(), expected.x(), TEST_EPSILON);
+        assert_approx_eq!(actual.y(), expected.y(), TEST_EPSILON);
+        assert_approx_eq!(actual.z(), expected.z(), TEST_EPSILON);
+    }
+
+    #[test]
+    fn new_should_scale_rotate_and_translate() {
+        let transform = Transform::new(
+            Vec3f::new(1.0, 2.0, 3.0),
+            Vec3f::new(0.0, 0.0, 90.0),
+            Vec3f::new(2.0, 1.0, 1.0),
+        );
+
+        assert_vector_eq(
+            transform.point(Vec3f::new(1.0, 0.0, 0.0)),
+            Vec3f::new(1.0, 4.0, 3.0),
+        );
+        assert_vector_eq(
+            transform.vector(Vec3f::new(1.0, 0.0, 0.0)),
+            Vec3f::new(0.0, 2.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn inverse_should_undo_transform() {
+        let transform = Transform::new(
+            Vec3f::new(1.0, -2.0, 0.5),
+            Vec3f::new(30.0, 45.0, -60.0),
+            Vec3f::new(0.5, 2.0, 3.0),
+        );
+        let point = Vec3f::new(0.3, -1.2, 4.0);
+
+        assert_vector_eq(
+            transform.inverse().point(transform.point(point)),
+            point,
+        );
+        assert_vector_eq(
+            transform.then(&transform.inverse()).point(point),
+            point,
+        );
+    }
+
+    #[test]
+    fn then_should_apply_outer_transform_last() {
+        let inner = Transform::new(
+            Vec3f::new(1.0, 0.0, 0.0),
+            Vec3f::zero(),
+            Vec3f::one(),
+        );
+        let outer = Transform::new(
+            Vec3f::zero(),
+            Vec3f::zero(),
+            Vec3f::new(2.0, 2.0, 2.0),
+        );
+
+        assert_vector_eq(
+            inner.then(&outer).point(Vec3f::zero()),
+            Vec3f::new(2.0, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn normal_should_stay_perpendicular_when_scaled() {
+        let transform = Transform::new(
+            Vec3f::zero(),
+            Vec3f::zero(),
+            Vec3f::new(4.0, 1.0, 1.0),
+        );
+        let tangent = transform.vector(Vec3f::new(1.0, -1.0, 0.0));
+        let normal = transform.normal(Vec3f::new(1.0, 1.0, 0.0));
+
+        assert_approx_eq!(tangent.dot(normal), 0.0, TEST_EPSILON);
+    }
+}
@@ -1,21 +1,45 @@
 use std::{
-    f64,
+    f64, fmt,
     ops::{Add, Div, Mul, Neg, Sub},
 };
 
 use crate::random::{self};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Precision of the vector components, single precision with the `f32`
+/// feature. Vectors take and return `f64` either way.
+#[cfg(feature = "f32")]
+pub type Float = f32;
+#[cfg(not(feature = "f32"))]
+pub type Float = f64;
+
+/// Tolerance of tests comparing the results of vector operations.
+#[cfg(test)]
+pub(crate) const TEST_EPSILON: f64 =
+    if cfg!(feature = "f32") { 1e-5 } else { 1e-9 };
+
+/// Converts a component for the `f64` interface, which is a no-op without
+/// the `f32` feature.
+#[allow(clippy::unnecessary_cast)]
+#[inline]
+pub(crate) fn widen(component: Float) -> f64 {
+    component as f64
+}
+
+#[derive(Clone, Copy, PartialEq)]
 pub struct Vec3f {
-    // TODO make it an array? does that impact performance?
-    x: f64,
-    y: f64,
-    z: f64,
+    x: Float,
+    y: Float,
+    z: Float,
 }
 
 impl Vec3f {
+    #[inline]
     pub fn new(x: f64, y: f64, z: f64) -> Self {
-        Self { x, y, z }
+        Self {
+            x: x as Float,
+            y: y as Float,
+            z: z as Float,
+        }
     }
 
     pub fn zero() -> Self {
@@ -79,18 +103,21 @@ impl Vec3f {
         f64::sqrt(self.magnitude_squared())
     }
 
+    #[inline]
     pub fn magnitude_squared(&self) -> f64 {
-        self.x * self.x + self.y * self.y + self.z * self.z
+        self.dot(*self)
     }
 
     pub fn unit(self) -> Self {
         self / self.magnitude()
     }
 
+    #[inline]
     pub fn dot(&self, other: Vec3f) -> f64 {
-        self.x * other.x + self.y * other.y + self.z * other.z
+        widen(self.x * other.x + self.y * other.y + self.z * other.z)
     }
 
+    #[inline]
     pub fn cross(&self, other: Vec3f) -> Vec3f {
         Self {
             x: self.y * other.z - self.z * other.y,
@@ -100,43 +127,64 @@ impl Vec3f {
     }
 
     pub fn is_near_zero(&self) -> bool {
-        f64::abs(self.x) < 1e-8
-            && f64::abs(self.y) < 1e-8
-            && f64::abs(self.z) < 1e-8
+        [self.x, self.y, self.z]
+            .iter()
+            .all(|component| component.abs() < 1e-8)
     }
 
     pub fn reflect(&self, normal: Vec3f) -> Vec3f {
         *self - normal * self.dot(normal) * 2.0
     }
 
+    /// The components at their stored precision, e.g. to load them into
+    /// SIMD lanes.
+    #[inline]
+    pub(crate) fn components(&self) -> [Float; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    #[inline]
     pub fn x(&self) -> f64 {
-        self.x
+        widen(self.x)
     }
 
     pub fn set_x(&mut self, x: f64) {
-        self.x = x;
+        *self = Self::new(x, self.y(), self.z());
     }
 
+    #[inline]
     pub fn y(&self) -> f64 {
-        self.y
+        widen(self.y)
     }
 
     pub fn set_y(&mut self, y: f64) {
-        self.y = y;
+        *self = Self::new(self.x(), y, self.z());
     }
 
+    #[inline]
     pub fn z(&self) -> f64 {
-        self.z
+        widen(self.z)
     }
 
     pub fn set_z(&mut self, z: f64) {
-        self.z = z;
+        *self = Self::new(self.x(), self.y(), z);
+    }
+}
+
+impl fmt::Debug for Vec3f {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vec3f")
+            .field("x", &self.x())
+            .field("y", &self.y())
+            .field("z", &self.z())
+            .finish()
     }
 }
 
 impl Add for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn add(self, rhs: Self) -> Self {
         Self {
             x: self.x + rhs.x,
@@ -149,6 +197,7 @@ impl Add for Vec3f {
 impl Sub for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn sub(self, rhs: Self) -> Self {
         Self {
             x: self.x - rhs.x,
@@ -161,7 +210,9 @@ impl Sub for Vec3f {
 impl Mul<f64> for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn mul(self, scalar: f64) -> Self {
+        let scalar = scalar as Float;
         Self {
             x: self.x * scalar,
             y: self.y * scalar,
@@ -173,6 +224,7 @@ impl Mul<f64> for Vec3f {
 impl Mul<u32> for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn mul(self, scalar: u32) -> Self {
         self * (scalar as f64)
     }
@@ -181,7 +233,9 @@ impl Mul<u32> for Vec3f {
 impl Div<f64> for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn div(self, scalar: f64) -> Self {
+        let scalar = scalar as Float;
         Self {
             x: self.x / scalar,
             y: self.y / scalar,
@@ -193,6 +247,7 @@ impl Div<f64> for Vec3f {
 impl Neg for Vec3f {
     type Output = Self;
 
+    #[inline]
     fn neg(self) -> Self::Output {
         Self {
             x: -self.x,
@@ -222,9 +277,9 @@ mod tests {
     fn new_should_be_correct() {
         let vec = Vec3f::new(32.5, 44.3, 61.0);
 
-        assert_eq!(vec.x, 32.5);
-        assert_eq!(vec.y, 44.3);
-        assert_eq!(vec.z, 61.0);
+        assert_eq!(vec.x(), 32.5);
+        assert_eq!(vec.y(), widen(44.3 as Float));
+        assert_eq!(vec.z(), 61.0);
     }
 
     #[test]
@@ -234,9 +289,9 @@ mod tests {
 
         let c = a + b;
 
-        assert_eq!(c.x, 3.0);
-        assert_eq!(c.y, 3.0);
-        assert_eq!(c.z, 3.0);
+        assert_eq!(c.x(), 3.0);
+        assert_eq!(c.y(), 3.0);
+        assert_eq!(c.z(), 3.0);
     }
 
     #[test]
@@ -246,9 +301,9 @@ mod tests {
 
         let c = a - b;
 
-        assert_eq!(c.x, 1.0);
-        assert_eq!(c.y, 3.0);
-        assert_eq!(c.z, 5.0);
+        assert_eq!(c.x(), 1.0);
+        assert_eq!(c.y(), 3.0);
+        assert_eq!(c.z(), 5.0);
     }
 
     #[test]
@@ -256,9 +311,9 @@ mod tests {
         let a = Vec3f::new(2.0, 3.0, 4.0);
         let c = a * 5.0;
 
-        assert_eq!(c.x, 10.0);
-        assert_eq!(c.y, 15.0);
-        assert_eq!(c.z, 20.0);
+        assert_eq!(c.x(), 10.0);
+        assert_eq!(c.y(), 15.0);
+        assert_eq!(c.z(), 20.0);
     }
 
     #[test]
@@ -266,9 +321,9 @@ mod tests {
         let a = Vec3f::new(2.0, 3.0, 4.0);
         let c = a * 5;
 
-        assert_eq!(c.x, 10.0);
-        assert_eq!(c.y, 15.0);
-        assert_eq!(c.z, 20.0);
+        assert_eq!(c.x(), 10.0);
+        assert_eq!(c.y(), 15.0);
+        assert_eq!(c.z(), 20.0);
     }
 
     #[test]
@@ -276,9 +331,9 @@ mod tests {
         let a = Vec3f::new(2.0, 3.0, 4.0);
         let c = a / 2.0;
 
-        assert_eq!(c.x, 1.0);
-        assert_eq!(c.y, 1.5);
-        assert_eq!(c.z, 2.0);
+        assert_eq!(c.x(), 1.0);
+        assert_eq!(c.y(), 1.5);
+        assert_eq!(c.z(), 2.0);
     }
 
     #[test]
@@ -0,0 +1,210 @@
+use crate::{
+    math::{interval::Interval, ray::Ray},
+    ray_hit::{HitInterval, RayHitDetails, RayHitTest},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    /// Inside of either object.
+    Union,
+    /// Inside of both objects.
+    Intersection,
+    /// Inside of the first object but not the second.
+    Difference,
+}
+
+impl CsgOperation {
+    fn contains(self, in_first: bool, in_second: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_first || in_second,
+            CsgOperation::Intersection => in_first && in_second,
+            CsgOperation::Difference => in_first && !in_second,
+        }
+    }
+}
+
+/// Constructive solid geometry, a solid combined from two closed objects,
+/// e.g. a sphere with a hole drilled through or a lens made from two
+/// spheres. The surfaces keep the materials of the objects they belong to.
+pub struct Csg {
+    operation: CsgOperation,
+    first: Box<dyn RayHitTest>,
+    second: Box<dyn RayHitTest>,
+}
+
+impl Csg {
+    pub fn new(
+        operation: CsgOperation,
+        first: Box<dyn RayHitTest>,
+        second: Box<dyn RayHitTest>,
+    ) -> Self {
+        Self {
+            operation,
+            first,
+            second,
+        }
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+}
+
+impl RayHitTest for Csg {
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails> {
+        self.hit_intervals(ray)
+            .into_iter()
+            .flat_map(|interval| [interval.entry, interval.exit])
+            .find(|hit| ray_t.surrounds(hit.t()))
+    }
+
+    fn hit_intervals(&self, ray: &Ray) -> Vec<HitInterval> {
+        combine_intervals(
+            self.operation,
+            self.first.hit_intervals(ray),
+            self.second.hit_intervals(ray),
+        )
+    }
+}
+
+/// Combines the intervals of two objects along the same ray by walking
+/// through their boundaries in order and keeping track of which objects
+/// the ray is inside of.
+pub(crate) fn combine_intervals(
+    operation: CsgOperation,
+    first: Vec<HitInterval>,
+    second: Vec<HitInterval>,
+) -> Vec<HitInterval> {
+    let mut boundaries = Vec::with_capacity(2 * (first.len() + second.len()));
+    for (is_first, intervals) in [(true, first), (false, second)] {
+        for interval in intervals {
+            boundaries.push((is_first, true, interval.entry));
+            boundaries.push((is_first, false, interval.exit));
+        }
+    }
+    boundaries.sort_by(|a, b| a.2.t().total_cmp(&b.2.t()));
+
+    let mut combined = Vec::new();
+    let (mut in_first, mut in_second) = (false, false);
+    let mut entry = None;
+    for (is_first, is_entry, hit) in boundaries {
+        let was_inside = operation.contains(in_first, in_second);
+        if is_first {
+            in_first = is_entry;
+        } else {
+            in_second = is_entry;
+        }
+        let is_inside = operation.contains(in_first, in_second);
+
+        // The normals of all hits face the ray, so boundaries only need to
+        // be marked as entering or leaving the combined solid, e.g. where a
+        // difference is entered by leaving the second object.
+        if !was_inside && is_inside {
+            entry = Some(hit.with_front_face(true));
+        } else if was_inside
+            && !is_inside
+            && let Some(entry) = entry.take()
+            && entry.t() < hit.t()
+        {
+            combined.push(HitInterval {
+                entry,
+                exit: hit.with_front_face(false),
+            });
+        }
+    }
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_approx_eq,
+        math::vector3::{TEST_EPSILON, Vec3f},
+        utils::test_utils::sphere,
+    };
+
+    fn csg(operation: CsgOperation) -> Csg {
+        Csg::new(
+            operation,
+            sphere(Vec3f::new(-0.5, 0.0, 0.0), 1.0),
+            sphere(Vec3f::new(0.5, 0.0, 0.0), 1.0),
+        )
+    }
+
+    fn ray_along_x() -> Ray {
+        Ray::new(Vec3f::new(-5.0, 0.0, 0.0), Vec3f::new(1.0, 0.0, 0.0))
+    }
+
+    fn interval_bounds(intervals: &[HitInterval]) -> Vec<(f64, f64)> {
+        intervals
+            .iter()
+            .map(|interval| (interval.entry.t(), interval.exit.t()))
+            .collect()
+    }
+
+    #[test]
+    fn combine_intervals_should_apply_operation() {
+        let ray = ray_along_x();
+        for (operation, expected) in [
+            (CsgOperation::Union, vec![(3.5, 6.5)]),
+            (CsgOperation::Intersection, vec![(4.5, 5.5)]),
+            (CsgOperation::Difference, vec![(3.5, 4.5)]),
+        ] {
+            let intervals = csg(operation).hit_intervals(&ray);
+            let bounds = interval_bounds(&intervals);
+
+            assert_eq!(bounds.len(), expected.len(), "{:?}", operation);
+            for ((entry, exit), (expected_entry, expected_exit)) in
+                bounds.into_iter().zip(expected)
+            {
+                assert_approx_eq!(entry, expected_entry, TEST_EPSILON);
+                assert_approx_eq!(exit, expected_exit, TEST_EPSILON);
+            }
+            for interval in &intervals {
+                assert!(interval.entry.is_front_face());
+                assert!(!interval.exit.is_front_face());
+            }
+        }
+    }
+
+    #[test]
+    fn difference_should_hit_inside_of_second_object() {
+        let ray = ray_along_x();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let difference = csg(CsgOperation::Difference);
+
+        // Leaves the first sphere where it enters the second one, through
+        // a surface facing towards -x.
+        let exit = difference
+            .does_hit(&ray, &Interval::new(4.0, f64::INFINITY))
+            .unwrap();
+        assert_approx_eq!(exit.t(), 4.5, TEST_EPSILON);
+        assert_approx_eq!(exit.normal().x(), -1.0, TEST_EPSILON);
+        assert!(!exit.is_front_face());
+
+        // Starting inside of the subtracted sphere, the ray only hits the
+        // first sphere from behind.
+        let inside = Ray::new(Vec3f::new(0.2, 0.0, 0.0), ray.direction());
+        assert!(difference.does_hit(&inside, &ray_t).is_none());
+        let backwards = Ray::new(inside.origin(), -ray.direction());
+        let hit = difference.does_hit(&backwards, &ray_t).unwrap();
+        assert_approx_eq!(hit.t(), 0.7, TEST_EPSILON);
+        assert!(hit.is_front_face());
+    }
+
+    #[test]
+    fn group_should_merge_overlapping_intervals() {
+        let group = crate::object::group::Group::new(vec![
+            sphere(Vec3f::new(-0.5, 0.0, 0.0), 1.0),
+            sphere(Vec3f::new(0.5, 0.0, 0.0), 1.0),
+            sphere(Vec3f::new(5.0, 0.0, 0.0), 1.0),
+        ]);
+
+        let bounds = interval_bounds(&group.hit_intervals(&ray_along_x()));
+
+        assert_eq!(bounds.len(), 2);
+        assert_approx_eq!(bounds[0].1, 6.5, TEST_EPSILON);
+        assert_approx_eq!(bounds[1].0, 9.0, TEST_EPSILON);
+    }
+}
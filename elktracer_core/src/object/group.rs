@@ -0,0 +1,48 @@
+use crate::{
+    math::{interval::Interval, ray::Ray},
+    object::csg::{CsgOperation, combine_intervals},
+    ray_hit::{HitInterval, RayHitDetails, RayHitTest},
+};
+
+/// Objects intersected as one, e.g. the parts of an assembly that is placed
+/// several times by instances of the group.
+pub struct Group {
+    objects: Vec<Box<dyn RayHitTest>>,
+}
+
+impl Group {
+    pub fn new(objects: Vec<Box<dyn RayHitTest>>) -> Self {
+        Self { objects }
+    }
+
+    pub fn objects(&self) -> &[Box<dyn RayHitTest>] {
+        &self.objects
+    }
+}
+
+impl RayHitTest for Group {
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails> {
+        let mut hit_result = None;
+        let mut closest = ray_t.max();
+        for object in self.objects.iter() {
+            if let Some(hit) =
+                object.does_hit(ray, &Interval::new(ray_t.min(), closest))
+            {
+                closest = hit.t();
+                hit_result = Some(hit);
+            }
+        }
+        hit_result
+    }
+
+    /// The inside of a group is the union of the insides of its objects.
+    fn hit_intervals(&self, ray: &Ray) -> Vec<HitInterval> {
+        self.objects.iter().fold(Vec::new(), |intervals, object| {
+            combine_intervals(
+                CsgOperation::Union,
+                intervals,
+                object.hit_intervals(ray),
+            )
+        })
+    }
+}
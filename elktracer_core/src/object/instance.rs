@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::{
+    math::{interval::Interval, ray::Ray, transform::Transform},
+    ray_hit::{HitInterval, RayHitDetails, RayHitTest},
+};
+
+/// Places a shared object with a transform. All instances of an object
+/// share its geometry, which is defined in object space.
+pub struct Instance {
+    object: Arc<dyn RayHitTest>,
+    transform: Transform,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn RayHitTest>, transform: Transform) -> Self {
+        Self { object, transform }
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl RayHitTest for Instance {
+    /// Intersects the ray transformed into object space. Its direction is
+    /// not normalized, so the interval along the ray stays valid.
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails> {
+        self.object
+            .does_hit(&self.transform.inverse_ray(ray), ray_t)
+            .map(|hit| hit.transformed(&self.transform))
+    }
+
+    fn hit_intervals(&self, ray: &Ray) -> Vec<HitInterval> {
+        self.object
+            .hit_intervals(&self.transform.inverse_ray(ray))
+            .into_iter()
+            .map(|interval| HitInterval {
+                entry: interval.entry.transformed(&self.transform),
+                exit: interval.exit.transformed(&self.transform),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_approx_eq,
+        math::vector3::{TEST_EPSILON, Vec3f},
+        object::group::Group,
+        utils::test_utils::sphere,
+    };
+
+    #[test]
+    fn instances_should_share_transformed_geometry() {
+        let group: Arc<dyn RayHitTest> =
+            Arc::new(Group::new(vec![sphere(Vec3f::zero(), 1.0)]));
+        // An ellipsoid stretched along x, moved to x = 10.
+        let instance = Instance::new(
+            group.clone(),
+            Transform::new(
+                Vec3f::new(10.0, 0.0, 0.0),
+                Vec3f::zero(),
+                Vec3f::new(2.0, 1.0, 1.0),
+            ),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let hit = instance
+            .does_hit(
+                &Ray::new(Vec3f::zero(), Vec3f::new(1.0, 0.0, 0.0)),
+                &ray_t,
+            )
+            .unwrap();
+        assert_approx_eq!(hit.t(), 8.0, TEST_EPSILON);
+        assert_approx_eq!(hit.point().x(), 8.0, TEST_EPSILON);
+        assert_approx_eq!(hit.normal().x(), -1.0, TEST_EPSILON);
+
+        let side_hit = instance
+            .does_hit(
+                &Ray::new(
+                    Vec3f::new(10.0, 5.0, 0.0),
+                    Vec3f::new(0.0, -1.0, 0.0),
+                ),
+                &ray_t,
+            )
+            .unwrap();
+        assert_approx_eq!(side_hit.t(), 4.0, TEST_EPSILON);
+        assert_eq!(Arc::strong_count(&group), 2);
+    }
+}
@@ -1 +1,6 @@
+pub mod csg;
+pub mod group;
+pub mod instance;
+pub mod motion;
 pub mod sphere;
+pub mod sphere_set;
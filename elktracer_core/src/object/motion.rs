@@ -0,0 +1,92 @@
+use crate::math::vector3::Vec3f;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    /// Time in seconds.
+    pub time: f64,
+    pub position: Vec3f,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, position: Vec3f) -> Self {
+        Self { time, position }
+    }
+}
+
+/// Movement of an object during the camera shutter interval.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Motion {
+    /// Moves from the object's position with a constant velocity in units
+    /// per second.
+    Linear { velocity: Vec3f },
+    /// Moves linearly between keyframes sorted by time and holds the first
+    /// and last position outside of them.
+    Keyframes(Vec<Keyframe>),
+}
+
+impl Motion {
+    pub fn position_at(&self, position: Vec3f, time: f64) -> Vec3f {
+        match self {
+            Motion::Linear { velocity } => position + *velocity * time,
+            Motion::Keyframes(keyframes) => {
+                let Some(first) = keyframes.first() else {
+                    return position;
+                };
+                let next =
+                    keyframes.iter().position(|keyframe| keyframe.time > time);
+
+                match next {
+                    Some(0) => first.position,
+                    Some(index) => {
+                        let from = keyframes[index - 1];
+                        let to = keyframes[index];
+                        let t = (time - from.time) / (to.time - from.time);
+                        from.position + (to.position - from.position) * t
+                    }
+                    None => keyframes[keyframes.len() - 1].position,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_at_should_interpolate_between_keyframes() {
+        let motion = Motion::Keyframes(vec![
+            Keyframe::new(0.0, Vec3f::new(0.0, 0.0, 0.0)),
+            Keyframe::new(1.0, Vec3f::new(2.0, 0.0, 0.0)),
+            Keyframe::new(2.0, Vec3f::new(2.0, 4.0, 0.0)),
+        ]);
+        let position = Vec3f::zero();
+
+        assert_eq!(
+            motion.position_at(position, 0.5),
+            Vec3f::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            motion.position_at(position, 1.5),
+            Vec3f::new(2.0, 2.0, 0.0)
+        );
+        assert_eq!(motion.position_at(position, -1.0), Vec3f::zero());
+        assert_eq!(
+            motion.position_at(position, 3.0),
+            Vec3f::new(2.0, 4.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn position_at_should_move_with_velocity() {
+        let motion = Motion::Linear {
+            velocity: Vec3f::new(0.0, -2.0, 0.0),
+        };
+
+        assert_eq!(
+            motion.position_at(Vec3f::new(1.0, 1.0, 1.0), 0.25),
+            Vec3f::new(1.0, 0.5, 1.0)
+        );
+    }
+}
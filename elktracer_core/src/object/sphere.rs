@@ -1,15 +1,17 @@
-use std::sync::Arc;
+use std::{f64, sync::Arc};
 
 use crate::{
     material::Material,
     math::{interval::Interval, ray::Ray, vector3::Vec3f},
-    ray_hit::{RayHitDetails, RayHitTest},
+    object::motion::Motion,
+    ray_hit::{HitInterval, RayHitDetails, RayHitTest},
 };
 
 pub struct Sphere {
     center_position: Vec3f,
     radius: f64,
     material: Arc<dyn Material>,
+    motion: Option<Motion>,
 }
 
 impl Sphere {
@@ -22,17 +24,48 @@ impl Sphere {
             center_position,
             radius,
             material,
+            motion: None,
         }
     }
-}
 
-impl RayHitTest for Sphere {
-    fn does_hit(
-        &mut self,
-        ray: &Ray,
-        ray_t: &Interval,
-    ) -> Option<RayHitDetails> {
-        let origin_center = self.center_position - ray.origin();
+    pub fn with_motion(mut self, motion: Option<Motion>) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    pub(super) fn center_position(&self) -> Vec3f {
+        self.center_position
+    }
+
+    pub(super) fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub(super) fn is_moving(&self) -> bool {
+        self.motion.is_some()
+    }
+
+    fn center_at(&self, time: f64) -> Vec3f {
+        match &self.motion {
+            Some(motion) => motion.position_at(self.center_position, time),
+            None => self.center_position,
+        }
+    }
+
+    /// Maps a point on the unit sphere to (u, v) in 0..1, u running around
+    /// the Y axis starting at -X and v from -Y to +Y.
+    fn get_uv(point: Vec3f) -> (f64, f64) {
+        let theta = f64::acos(-point.y());
+        let phi = f64::atan2(-point.z(), point.x()) + f64::consts::PI;
+
+        (phi / (2.0 * f64::consts::PI), theta / f64::consts::PI)
+    }
+
+    /// Returns the center at the time of the ray and the distances along the
+    /// ray where it enters and leaves the sphere, if it hits it at all.
+    fn roots(&self, ray: &Ray) -> Option<(Vec3f, f64, f64)> {
+        let center_position = self.center_at(ray.time());
+        let origin_center = center_position - ray.origin();
         let a = ray.direction().magnitude_squared();
         let h = ray.direction().dot(origin_center);
 
@@ -44,23 +77,98 @@ impl RayHitTest for Sphere {
         }
 
         let discriminant_sqrt = f64::sqrt(discriminant);
+        Some((
+            center_position,
+            (h - discriminant_sqrt) / a,
+            (h + discriminant_sqrt) / a,
+        ))
+    }
 
-        // Find the nearest root that lies in the acceptable range.
-        let mut root = (h - discriminant_sqrt) / a;
-        if !ray_t.surrounds(root) {
-            root = (h + discriminant_sqrt) / a;
-            if !ray_t.surrounds(root) {
-                return None;
-            }
-        }
-
+    pub(super) fn hit_details(
+        &self,
+        ray: &Ray,
+        root: f64,
+        center_position: Vec3f,
+    ) -> RayHitDetails {
         let point = ray.at(root);
-        Some(RayHitDetails::from(
+        let outward_normal = (point - center_position) / self.radius;
+        RayHitDetails::from(
             point,
             root,
             ray,
-            (point - self.center_position) / self.radius,
+            outward_normal,
+            Self::get_uv(outward_normal),
             self.material.clone(),
-        ))
+        )
+    }
+}
+
+impl RayHitTest for Sphere {
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails> {
+        let (center_position, near, far) = self.roots(ray)?;
+
+        // Find the nearest root that lies in the acceptable range.
+        let root = if ray_t.surrounds(near) {
+            near
+        } else if ray_t.surrounds(far) {
+            far
+        } else {
+            return None;
+        };
+
+        Some(self.hit_details(ray, root, center_position))
+    }
+
+    fn hit_intervals(&self, ray: &Ray) -> Vec<HitInterval> {
+        match self.roots(ray) {
+            // Rays grazing the sphere don't pass through its inside.
+            Some((center_position, near, far)) if near < far => {
+                vec![HitInterval {
+                    entry: self.hit_details(ray, near, center_position),
+                    exit: self.hit_details(ray, far, center_position),
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_approx_eq, utils::test_utils::gray_material};
+
+    #[test]
+    fn get_uv_should_be_correct() {
+        let epsilon = 1e-9;
+        {
+            let (u, v) = Sphere::get_uv(Vec3f::new(1.0, 0.0, 0.0));
+            assert_approx_eq!(u, 0.5, epsilon);
+            assert_approx_eq!(v, 0.5, epsilon);
+        }
+        {
+            let (u, v) = Sphere::get_uv(Vec3f::new(0.0, 1.0, 0.0));
+            assert_approx_eq!(u, 0.5, epsilon);
+            assert_approx_eq!(v, 1.0, epsilon);
+        }
+        {
+            let (u, v) = Sphere::get_uv(Vec3f::new(0.0, 0.0, 1.0));
+            assert_approx_eq!(u, 0.25, epsilon);
+            assert_approx_eq!(v, 0.5, epsilon);
+        }
+    }
+
+    #[test]
+    fn does_hit_should_use_center_at_ray_time() {
+        let sphere = Sphere::new(Vec3f::zero(), 1.0, gray_material())
+            .with_motion(Some(Motion::Linear {
+                velocity: Vec3f::new(0.0, 10.0, 0.0),
+            }));
+        let ray =
+            Ray::new(Vec3f::new(0.0, 0.0, -5.0), Vec3f::new(0.0, 0.0, 1.0));
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        assert!(sphere.does_hit(&ray, &ray_t).is_some());
+        assert!(sphere.does_hit(&ray.with_time(1.0), &ray_t).is_none());
     }
 }
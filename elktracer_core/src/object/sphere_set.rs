@@ -0,0 +1,232 @@
+use crate::{
+    math::{
+        interval::Interval,
+        ray::Ray,
+        simd::{self, LANE_COUNT, Lanes, Vec3Lanes},
+        vector3::{Float, widen},
+    },
+    object::{
+        csg::{CsgOperation, combine_intervals},
+        sphere::Sphere,
+    },
+    ray_hit::{HitInterval, RayHitDetails, RayHitTest},
+};
+
+/// Spheres intersected as one, with the centers and radii stored in SIMD
+/// lanes so that a ray is tested against `LANE_COUNT` spheres at once.
+/// Moving spheres are tested one by one.
+pub struct SphereSet {
+    spheres: Vec<Sphere>,
+    centers: Vec<Vec3Lanes>,
+    radii_squared: Vec<Lanes>,
+    moving: Vec<Sphere>,
+}
+
+impl SphereSet {
+    pub fn new(spheres: Vec<Sphere>) -> Self {
+        let (moving, spheres): (Vec<_>, Vec<_>) =
+            spheres.into_iter().partition(Sphere::is_moving);
+
+        let centers = spheres
+            .chunks(LANE_COUNT)
+            .map(|chunk| {
+                Vec3Lanes::from_slice(
+                    &chunk
+                        .iter()
+                        .map(Sphere::center_position)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        let radii_squared = spheres
+            .chunks(LANE_COUNT)
+            .map(|chunk| {
+                let mut radii_squared = [Float::NAN; LANE_COUNT];
+                for (lane, sphere) in chunk.iter().enumerate() {
+                    radii_squared[lane] =
+                        (sphere.radius() * sphere.radius()) as Float;
+                }
+                Lanes::new(radii_squared)
+            })
+            .collect();
+
+        Self {
+            spheres,
+            centers,
+            radii_squared,
+            moving,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.spheres.len() + self.moving.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the index of the closest static sphere hit within `ray_t`
+    /// and the distance along the ray.
+    fn closest_static_hit(
+        &self,
+        ray: &Ray,
+        ray_t: &Interval,
+    ) -> Option<(usize, f64)> {
+        let origin = Vec3Lanes::splat(ray.origin());
+        let direction = Vec3Lanes::splat(ray.direction());
+        let a = direction.dot(&direction);
+        let min = Lanes::splat(ray_t.min() as Float);
+
+        let mut closest = None;
+        let mut closest_t = ray_t.max();
+        for (chunk, (centers, radii_squared)) in
+            self.centers.iter().zip(&self.radii_squared).enumerate()
+        {
+            let origin_center = *centers - origin;
+            let h = direction.dot(&origin_center);
+            let discriminant = h * h
+                - a * (origin_center.dot(&origin_center) - *radii_squared);
+            if !simd::any_non_negative(discriminant) {
+                continue;
+            }
+
+            // Same as `Sphere::does_hit`, the near root if it lies in the
+            // acceptable range and the far one otherwise.
+            let discriminant_sqrt = discriminant.sqrt();
+            let max = Lanes::splat(closest_t as Float);
+            let near =
+                simd::select_between((h - discriminant_sqrt) / a, min, max);
+            let far =
+                simd::select_between((h + discriminant_sqrt) / a, min, max);
+            let roots = near.min(far);
+
+            for (lane, root) in roots.to_array().into_iter().enumerate() {
+                let root = widen(root);
+                if root < closest_t {
+                    closest_t = root;
+                    closest = Some(chunk * LANE_COUNT + lane);
+                }
+            }
+        }
+
+        closest.map(|index| (index, closest_t))
+    }
+}
+
+impl RayHitTest for SphereSet {
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails> {
+        let mut hit_result =
+            self.closest_static_hit(ray, ray_t).map(|(index, root)| {
+                let sphere = &self.spheres[index];
+                sphere.hit_details(ray, root, sphere.center_position())
+            });
+        let mut closest =
+            hit_result.as_ref().map_or(ray_t.max(), |hit| hit.t());
+        for sphere in self.moving.iter() {
+            if let Some(hit) =
+                sphere.does_hit(ray, &Interval::new(ray_t.min(), closest))
+            {
+                closest = hit.t();
+                hit_result = Some(hit);
+            }
+        }
+        hit_result
+    }
+
+    /// The inside of a set is the union of the insides of its spheres.
+    fn hit_intervals(&self, ray: &Ray) -> Vec<HitInterval> {
+        self.spheres.iter().chain(self.moving.iter()).fold(
+            Vec::new(),
+            |intervals, sphere| {
+                combine_intervals(
+                    CsgOperation::Union,
+                    intervals,
+                    sphere.hit_intervals(ray),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_approx_eq,
+        math::vector3::{TEST_EPSILON, Vec3f},
+        object::{group::Group, motion::Motion},
+        utils::test_utils::gray_material,
+    };
+
+    fn spheres() -> Vec<Sphere> {
+        let material = gray_material();
+        let mut spheres = (0..11)
+            .map(|index| {
+                let t = index as f64;
+                Sphere::new(
+                    Vec3f::new(t.sin() * 3.0, t.cos() * 2.0, -5.0 - t),
+                    0.5 + 0.1 * t,
+                    material.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        spheres.push(
+            Sphere::new(Vec3f::new(0.0, 0.0, -3.0), 0.5, material).with_motion(
+                Some(Motion::Linear {
+                    velocity: Vec3f::new(2.0, 0.0, 0.0),
+                }),
+            ),
+        );
+        spheres
+    }
+
+    #[test]
+    fn does_hit_should_match_a_group_of_the_spheres() {
+        let set = SphereSet::new(spheres());
+        let group = Group::new(
+            spheres()
+                .into_iter()
+                .map(|sphere| Box::new(sphere) as Box<dyn RayHitTest>)
+                .collect(),
+        );
+        assert_eq!(set.len(), 12);
+
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let mut hits = 0;
+        for index in 0..400 {
+            let t = index as f64 * 0.05;
+            let ray = Ray::new(
+                Vec3f::new(0.0, 0.0, if index % 2 == 0 { 0.0 } else { -7.0 }),
+                Vec3f::new(t.sin() * 0.6, (t * 1.7).cos() * 0.4, -1.0),
+            )
+            .with_time(t % 1.0);
+
+            let expected = group.does_hit(&ray, &ray_t);
+            let actual = set.does_hit(&ray, &ray_t);
+            assert_eq!(actual.is_some(), expected.is_some());
+            if let (Some(actual), Some(expected)) = (actual, expected) {
+                hits += 1;
+                // The roots are computed at the precision of the lanes,
+                // which the normals scale by the inverse radius.
+                assert_approx_eq!(actual.t() / expected.t(), 1.0, TEST_EPSILON);
+                assert_approx_eq!(
+                    (actual.normal() - expected.normal()).magnitude(),
+                    0.0,
+                    TEST_EPSILON * 10.0
+                );
+                assert_eq!(actual.is_front_face(), expected.is_front_face());
+            }
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn does_hit_should_respect_the_interval() {
+        let set = SphereSet::new(spheres());
+        let ray = Ray::new(Vec3f::zero(), Vec3f::new(0.0, 0.0, -1.0));
+
+        assert!(set.does_hit(&ray, &Interval::new(0.001, 2.0)).is_none());
+        assert!(SphereSet::new(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    checkpoint::CheckpointSettings,
+    error::{self, Error},
+    film::AccumulationBuffer,
+    filter::FilterType,
+    raytracer::{RenderOptions, RenderRegion, image::Image},
+    utils::read_array,
+};
+
+const MAGIC: &[u8; 8] = b"ELKPART\0";
+const VERSION: u32 = 2;
+
+/// Unnormalized sample sums of a render region. Buffers rendered on
+/// different machines for other regions or sample ranges of the same image
+/// are merged into the final image.
+pub struct PartialBuffer {
+    scene_hash: u64,
+    image_width: u32,
+    image_height: u32,
+    options_hash: u64,
+    exposure: f64,
+    buffer: AccumulationBuffer,
+}
+
+/// Hash of the options that have to match for buffers to be merged.
+fn options_hash(options: &RenderOptions) -> u64 {
+    CheckpointSettings::hash(&options.sample_bytes())
+}
+
+impl PartialBuffer {
+    pub(crate) fn new(
+        options: &RenderOptions,
+        scene_hash: u64,
+        exposure: f64,
+        buffer: AccumulationBuffer,
+    ) -> Self {
+        Self {
+            scene_hash,
+            image_width: options.image_width,
+            image_height: options.image_height(),
+            options_hash: options_hash(options),
+            exposure,
+            buffer,
+        }
+    }
+
+    /// Identifies the scene the buffer belongs to, see
+    /// `CheckpointSettings::hash`.
+    pub fn scene_hash(&self) -> u64 {
+        self.scene_hash
+    }
+
+    pub fn region(&self) -> RenderRegion {
+        self.buffer.region()
+    }
+
+    pub fn image_width(&self) -> u32 {
+        self.image_width
+    }
+
+    pub fn image_height(&self) -> u32 {
+        self.image_height
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> error::Result<()> {
+        let path = path.as_ref();
+        self.write_to(path).map_err(|error| {
+            Error::PartialBuffer(format!("{}: {}", path.display(), error))
+        })
+    }
+
+    fn write_to(&self, path: &Path) -> io::Result<()> {
+        let region = self.region();
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&self.scene_hash.to_le_bytes())?;
+        writer.write_all(&self.options_hash.to_le_bytes())?;
+        writer.write_all(&self.exposure.to_le_bytes())?;
+        for value in [
+            self.image_width,
+            self.image_height,
+            region.x,
+            region.y,
+            region.width,
+            region.height,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        self.buffer.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> error::Result<Self> {
+        let path = path.as_ref();
+        let io_error = |error: io::Error| {
+            Error::PartialBuffer(format!("{}: {}", path.display(), error))
+        };
+        let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+
+        let magic: [u8; 8] = read_array(&mut reader, io_error)?;
+        let version = u32::from_le_bytes(read_array(&mut reader, io_error)?);
+        if &magic != MAGIC || version != VERSION {
+            return Err(Error::PartialBuffer(format!(
+                "{} is not a partial buffer of this version",
+                path.display()
+            )));
+        }
+
+        let scene_hash = u64::from_le_bytes(read_array(&mut reader, io_error)?);
+        let options_hash =
+            u64::from_le_bytes(read_array(&mut reader, io_error)?);
+        let exposure = f64::from_le_bytes(read_array(&mut reader, io_error)?);
+        let mut read_u32 = || -> error::Result<u32> {
+            Ok(u32::from_le_bytes(read_array(&mut reader, io_error)?))
+        };
+        let image_width = read_u32()?;
+        let image_height = read_u32()?;
+        let region = RenderRegion::new(
+            read_u32()?,
+            read_u32()?,
+            read_u32()?,
+            read_u32()?,
+        );
+
+        // Only the sums are needed for merging, not the filter.
+        let mut buffer = AccumulationBuffer::new(
+            region,
+            FilterType::default(),
+            FilterType::default().default_radius(),
+        )?;
+        buffer.read_from(&mut reader).map_err(io_error)?;
+
+        Ok(Self {
+            scene_hash,
+            image_width,
+            image_height,
+            options_hash,
+            exposure,
+            buffer,
+        })
+    }
+
+    /// Adds up the buffers into a full size image. Sums of the same pixel
+    /// are added, so buffers of the same region average their samples.
+    /// Pixels outside of all regions stay transparent.
+    pub fn merge(partials: &[PartialBuffer]) -> error::Result<Image> {
+        let Some(first) = partials.first() else {
+            return Err(Error::IncompatiblePartialBuffers(
+                "No partial buffers to merge".to_string(),
+            ));
+        };
+        for partial in partials {
+            if (partial.image_width, partial.image_height)
+                != (first.image_width, first.image_height)
+            {
+                return Err(Error::IncompatiblePartialBuffers(format!(
+                    "Image size {}x{} differs from {}x{}",
+                    partial.image_width,
+                    partial.image_height,
+                    first.image_width,
+                    first.image_height
+                )));
+            }
+            if partial.scene_hash != first.scene_hash {
+                return Err(Error::IncompatiblePartialBuffers(
+                    "Buffers were rendered from different scenes".to_string(),
+                ));
+            }
+            if partial.options_hash != first.options_hash
+                || partial.exposure != first.exposure
+            {
+                return Err(Error::IncompatiblePartialBuffers(
+                    "Buffers were rendered with different options".to_string(),
+                ));
+            }
+        }
+
+        let image_region =
+            RenderRegion::new(0, 0, first.image_width, first.image_height);
+        let mut merged = AccumulationBuffer::new(
+            image_region,
+            FilterType::default(),
+            FilterType::default().default_radius(),
+        )?;
+        let mut image = Image::try_new(first.image_width, first.image_height)?;
+        for partial in partials {
+            merged.add(&partial.buffer);
+        }
+
+        for partial in partials {
+            let region = partial
+                .region()
+                .clamp(image_region.width, image_region.height);
+            for y in region.y..region.y + region.height {
+                for x in region.x..region.x + region.width {
+                    let color = merged.resolve(x, y) * first.exposure;
+                    image.set_pixel(x, y, color.as_rgba());
+                }
+            }
+        }
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn partial(
+        options: &RenderOptions,
+        region: RenderRegion,
+        color: Color,
+    ) -> PartialBuffer {
+        let mut buffer =
+            AccumulationBuffer::new(region, FilterType::Box, 0.5).unwrap();
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                buffer.splat(x as f64, y as f64, color);
+            }
+        }
+        PartialBuffer::new(options, 0, 1.0, buffer)
+    }
+
+    #[test]
+    fn merge_should_average_sample_ranges_and_place_tiles() {
+        let options = RenderOptions::new(4, 2.0, 1, 1);
+        let white = Color::new(1.0, 1.0, 1.0);
+        let black = Color::new(0.0, 0.0, 0.0);
+        let top = RenderRegion::tile(4, 2, 0, 2).unwrap();
+
+        let image = PartialBuffer::merge(&[
+            partial(&options, top, white),
+            partial(&options, top, black),
+        ])
+        .unwrap();
+
+        // Gamma corrected 0.5
+        assert_eq!(image.get_pixel(2, 0).unwrap().r(), 181);
+        assert_eq!(image.get_pixel(2, 0).unwrap().a(), 255);
+        assert_eq!(image.get_pixel(2, 1).unwrap().a(), 0);
+    }
+
+    #[test]
+    fn options_hash_should_ignore_region_and_sample_range() {
+        let options = RenderOptions::new(4, 2.0, 1, 1);
+        let mut tile = options;
+        tile.samples_per_pixel = 16;
+        tile.sample_offset = 16;
+        tile.region = Some(RenderRegion::new(0, 1, 4, 1));
+        tile.crop_to_region = true;
+        let mut other_filter = options;
+        other_filter.filter_radius = 1.5;
+
+        assert_eq!(options_hash(&tile), options_hash(&options));
+        assert_ne!(options_hash(&other_filter), options_hash(&options));
+    }
+
+    #[test]
+    fn merge_should_refuse_different_scenes() {
+        let options = RenderOptions::new(4, 2.0, 1, 1);
+        let region = RenderRegion::new(0, 0, 4, 2);
+        let white = Color::new(1.0, 1.0, 1.0);
+        let mut other = partial(&options, region, white);
+        other.scene_hash = 1;
+
+        let result =
+            PartialBuffer::merge(&[partial(&options, region, white), other]);
+
+        assert!(matches!(result, Err(Error::IncompatiblePartialBuffers(_))));
+    }
+
+    #[test]
+    fn load_should_read_saved_buffer() {
+        let options = RenderOptions::new(4, 2.0, 1, 1);
+        let region = RenderRegion::new(1, 0, 2, 2);
+        let mut saved = partial(&options, region, Color::new(0.5, 0.5, 0.5));
+        saved.scene_hash = 42;
+        let path = std::env::temp_dir()
+            .join(format!("elktracer_partial_{}.bin", std::process::id()));
+
+        saved.save(&path).unwrap();
+        let loaded = PartialBuffer::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.scene_hash, 42);
+        assert_eq!(loaded.region(), region);
+        assert_eq!(
+            PartialBuffer::merge(&[loaded]).unwrap(),
+            PartialBuffer::merge(&[saved]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::{
+    camera::Camera,
+    error,
+    material::Material,
+    math::{interval::Interval, vector3::Vec3f},
+    ray_hit::RayHitTest,
+    raytracer::RenderOptions,
+};
+
+/// First object seen through an image position, see `pick_object`.
+#[derive(Clone)]
+pub struct PickedObject {
+    /// Index of the object in the list passed to `pick_object`.
+    pub object_index: usize,
+    pub point: Vec3f,
+    /// Surface normal facing the camera.
+    pub normal: Vec3f,
+    /// Distance from the origin of the camera ray to the point.
+    pub distance: f64,
+    pub material: Arc<dyn Material>,
+}
+
+/// Traces the camera ray through the image position (`x`, `y`) in pixels of
+/// an image rendered with the options, whose pixel centers lie at integer
+/// positions. The ray starts at the center of the lens at the time the
+/// shutter opens, so that picking doesn't depend on random samples. Returns
+/// `None` if no object is hit or the position lies outside of the projected
+/// image.
+pub fn pick_object(
+    camera: &Camera,
+    objects: &[Box<dyn RayHitTest>],
+    options: &RenderOptions,
+    (x, y): (f64, f64),
+) -> error::Result<Option<PickedObject>> {
+    options.validate()?;
+    camera.validate()?;
+
+    let mut pinhole_camera = camera.clone();
+    pinhole_camera.defocus_angle = 0.0;
+    let projection = camera.projection.create_projection(
+        &pinhole_camera,
+        options.image_width,
+        options.image_height(),
+    );
+    let Some(ray) = projection
+        .get_ray(x, y)
+        .map(|ray| ray.with_time(camera.shutter_open))
+    else {
+        return Ok(None);
+    };
+
+    let mut picked = None;
+    let mut closest = f64::INFINITY;
+    for (object_index, object) in objects.iter().enumerate() {
+        if let Some(hit) = object.does_hit(&ray, &Interval::new(0.0, closest)) {
+            closest = hit.t();
+            picked = Some(PickedObject {
+                object_index,
+                point: hit.point(),
+                normal: hit.normal(),
+                distance: (hit.point() - ray.origin()).magnitude(),
+                material: hit.material,
+            });
+        }
+    }
+    Ok(picked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_approx_eq, math::vector3::TEST_EPSILON,
+        utils::test_utils::sphere,
+    };
+
+    #[test]
+    fn pick_object_should_return_closest_hit() {
+        let camera = Camera {
+            position: Vec3f::new(0.0, 0.0, 10.0),
+            look_at: Vec3f::zero(),
+            ..Camera::default()
+        };
+        let objects = vec![
+            sphere(Vec3f::new(0.0, 0.0, -5.0), 2.0),
+            sphere(Vec3f::zero(), 1.0),
+        ];
+        let options = RenderOptions::new(21, 1.0, 1, 1);
+
+        let picked = pick_object(&camera, &objects, &options, (10.0, 10.0))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(picked.object_index, 1);
+        assert_approx_eq!(picked.distance, 9.0, TEST_EPSILON);
+        assert_approx_eq!(picked.point.z(), 1.0, TEST_EPSILON);
+        assert_approx_eq!(picked.normal.z(), 1.0, TEST_EPSILON);
+
+        let missed = pick_object(&camera, &objects, &options, (0.0, 0.0));
+        assert!(missed.unwrap().is_none());
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU16, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::raytracer::image::Image;
+
+/// Handle to follow a render from another thread and to cancel it. Clones
+/// share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct RenderProgress {
+    state: Arc<ProgressState>,
+    preview_interval: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    completed_samples: AtomicU16,
+    samples_per_pixel: AtomicU16,
+    cancelled: AtomicBool,
+    preview: Mutex<Option<Image>>,
+}
+
+impl RenderProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves the samples traced so far into a preview image after a
+    /// sample pass, at most once per interval.
+    pub fn with_preview_interval(mut self, interval: Duration) -> Self {
+        self.preview_interval = Some(interval);
+        self
+    }
+
+    pub fn preview_interval(&self) -> Option<Duration> {
+        self.preview_interval
+    }
+
+    /// Samples per pixel traced so far, including those of a resumed
+    /// checkpoint.
+    pub fn completed_samples(&self) -> u16 {
+        self.state.completed_samples.load(Ordering::Relaxed)
+    }
+
+    /// Zero until the render has started.
+    pub fn samples_per_pixel(&self) -> u16 {
+        self.state.samples_per_pixel.load(Ordering::Relaxed)
+    }
+
+    /// Completed part of the render between zero and one.
+    pub fn fraction(&self) -> f64 {
+        match self.samples_per_pixel() {
+            0 => 0.0,
+            total => self.completed_samples() as f64 / total as f64,
+        }
+    }
+
+    /// Stops the render at the next row, which then fails with
+    /// `Error::RenderCancelled`.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Latest preview image, if the render has resolved one yet.
+    pub fn preview(&self) -> Option<Image> {
+        self.state
+            .preview
+            .lock()
+            .expect("Render progress preview lock poisoned")
+            .clone()
+    }
+
+    pub(crate) fn start(&self, completed_samples: u16, samples_per_pixel: u16) {
+        self.state
+            .completed_samples
+            .store(completed_samples, Ordering::Relaxed);
+        self.state
+            .samples_per_pixel
+            .store(samples_per_pixel, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_completed_samples(&self, completed_samples: u16) {
+        self.state
+            .completed_samples
+            .store(completed_samples, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_preview(&self, image: Image) {
+        *self
+            .state
+            .preview
+            .lock()
+            .expect("Render progress preview lock poisoned") = Some(image);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_should_share_progress() {
+        let progress = RenderProgress::new();
+        let observer = progress.clone();
+
+        progress.start(0, 4);
+        progress.set_completed_samples(1);
+        observer.cancel();
+
+        assert_eq!(observer.completed_samples(), 1);
+        assert_eq!(observer.fraction(), 0.25);
+        assert!(progress.is_cancelled());
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 pub fn random_f64_0_1() -> f64 {
     fastrand::f64()
 }
@@ -5,3 +7,31 @@ pub fn random_f64_0_1() -> f64 {
 pub fn random_f64_m1_1() -> f64 {
     (fastrand::f64() * 2.0) - 1.0
 }
+
+pub fn random_u32(range: Range<u32>) -> u32 {
+    fastrand::u32(range)
+}
+
+pub fn random_usize(range: Range<usize>) -> usize {
+    fastrand::usize(range)
+}
+
+/// State of the random number generator of the current thread.
+pub fn state() -> u64 {
+    fastrand::get_seed()
+}
+
+/// Restores a state returned by `state`.
+pub fn set_state(state: u64) {
+    fastrand::seed(state)
+}
+
+/// Deterministic state for renders starting at the sample index, so that
+/// renders of different sample ranges use different random numbers.
+pub fn sample_seed(sample_offset: u32) -> u64 {
+    // SplitMix64 finalizer
+    let mut seed = (sample_offset as u64).wrapping_add(0x9e3779b97f4a7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+    seed ^ (seed >> 31)
+}
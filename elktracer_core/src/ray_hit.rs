@@ -2,13 +2,16 @@ use std::sync::Arc;
 
 use crate::{
     material::Material,
-    math::{interval::Interval, ray::Ray, vector3::Vec3f},
+    math::{
+        interval::Interval, ray::Ray, transform::Transform, vector3::Vec3f,
+    },
 };
 
 pub struct RayHitDetails {
     point: Vec3f,
     t: f64,
     normal: Vec3f,
+    uv: (f64, f64),
     is_front_face: bool,
     // pub material: &'a mut dyn Material,
     pub material: Arc<dyn Material>,
@@ -20,6 +23,7 @@ impl RayHitDetails {
         t: f64,
         ray: &Ray,
         outward_normal: Vec3f,
+        uv: (f64, f64),
         material: Arc<dyn Material>,
     ) -> Self {
         let is_front_face = ray.direction().dot(outward_normal) < 0.0;
@@ -31,6 +35,7 @@ impl RayHitDetails {
             } else {
                 -outward_normal
             },
+            uv,
             is_front_face,
             material,
         }
@@ -44,6 +49,10 @@ impl RayHitDetails {
         self.point
     }
 
+    pub fn uv(&self) -> (f64, f64) {
+        self.uv
+    }
+
     pub fn t(&self) -> f64 {
         self.t
     }
@@ -51,12 +60,39 @@ impl RayHitDetails {
     pub fn is_front_face(&self) -> bool {
         self.is_front_face
     }
+
+    /// Maps a hit of a ray transformed into object space back into world
+    /// space. The distance along the ray is unchanged.
+    pub(crate) fn transformed(mut self, transform: &Transform) -> Self {
+        self.point = transform.point(self.point);
+        self.normal = transform.normal(self.normal).unit();
+        self
+    }
+
+    /// Marks the hit as entering or leaving a solid combined from other
+    /// objects. The normal already faces the ray and is kept.
+    pub(crate) fn with_front_face(mut self, is_front_face: bool) -> Self {
+        self.is_front_face = is_front_face;
+        self
+    }
 }
 
-pub trait RayHitTest {
-    fn does_hit(
-        &mut self,
-        ray: &Ray,
-        ray_t: &Interval,
-    ) -> Option<RayHitDetails>;
+/// Part of a ray inside a closed object, from the hit where the ray enters
+/// the object to the hit where it leaves it.
+pub struct HitInterval {
+    pub entry: RayHitDetails,
+    pub exit: RayHitDetails,
+}
+
+pub trait RayHitTest: Send + Sync {
+    fn does_hit(&self, ray: &Ray, ray_t: &Interval) -> Option<RayHitDetails>;
+
+    /// Returns all parts of the ray inside the object, sorted along the ray
+    /// and not overlapping. Unlike `does_hit` the whole line is tested,
+    /// including behind the ray origin, so that constructive solid geometry
+    /// knows which objects the origin is inside of. Objects that don't
+    /// enclose a volume return no intervals.
+    fn hit_intervals(&self, _ray: &Ray) -> Vec<HitInterval> {
+        Vec::new()
+    }
 }
@@ -1,120 +1,99 @@
-use std::fmt;
+use std::{fmt, str::FromStr, time::Instant};
 
 use crate::{
     camera::Camera,
+    checkpoint::{self, CheckpointSettings, CheckpointState},
     color::Color,
-    math::{interval::Interval, ray::Ray},
-    ray_hit::{RayHitDetails, RayHitTest},
+    error::{self, Error},
+    film::AccumulationBuffer,
+    filter::FilterType,
+    integrator::{Integrator, IntegratorType},
+    partial::PartialBuffer,
+    progress::RenderProgress,
+    random,
+    ray_hit::RayHitTest,
     raytracer_context::RaytracerContext,
+    scene::Scene,
+    spectrum,
+    stats::{self, RenderStats},
 };
 
-pub mod image {
-    use image::{ImageError, RgbaImage};
+pub mod image;
 
-    pub struct Rgba {
-        r: u8,
-        g: u8,
-        b: u8,
-        a: u8,
-    }
+/// Rectangle in pixel coordinates, starting at the upper left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
 
-    impl Rgba {
-        pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
-            Self { r, g, b, a }
+impl RenderRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
         }
     }
 
-    pub struct Image {
-        width: u32,
-        height: u32,
-        pixel_data: Vec<u8>,
-        channels: u32,
-    }
-
-    impl Image {
-        pub fn new(width: u32, height: u32) -> Self {
-            let channels = 4;
-            let pixel_data_length =
-                Self::assert_usize(width * height * channels);
-            log::trace!(
-                "Image :: new :: {}x{} :: {} channels :: pixel_data [{}]",
-                width,
-                height,
-                channels,
-                pixel_data_length
-            );
-            Self {
-                width,
-                height,
-                pixel_data: vec![0; pixel_data_length],
-                channels,
-            }
-        }
-
-        pub fn set_pixel(&mut self, x: u32, y: u32, rgba: Rgba) {
-            if x >= self.width || y >= self.height {
-                log::warn!(
-                    "Pixel coordinated out of bounds. Given x={} and y={}, image width={} and height={}. Pixel data not updated",
-                    x,
-                    y,
-                    self.width,
-                    self.height
-                );
-                return;
-            }
-
-            let index = ((y * self.width + x) * self.channels) as usize;
-            self.pixel_data[index] = rgba.r;
-            self.pixel_data[index + 1] = rgba.g;
-            self.pixel_data[index + 2] = rgba.b;
-            self.pixel_data[index + 3] = rgba.a;
-        }
-
-        pub fn data(&self) -> Vec<u8> {
-            self.pixel_data.to_vec()
-        }
-
-        pub fn width(&self) -> u32 {
-            self.width
-        }
-
-        pub fn height(&self) -> u32 {
-            self.height
-        }
-
-        fn assert_usize(value: u32) -> usize {
-            match usize::try_from(value) {
-                Ok(result) => result,
-                Err(_) => panic!("Unable to convert u32 '{}' to usize", value),
-            }
+    /// Splits the image into `count` horizontal strips of almost equal
+    /// height and returns the strip at `index`, or `None` if the index is
+    /// out of range or the image has fewer rows than strips.
+    pub fn tile(
+        image_width: u32,
+        image_height: u32,
+        index: u32,
+        count: u32,
+    ) -> Option<Self> {
+        if index >= count || count > image_height {
+            return None;
         }
+        let start = (image_height as u64 * index as u64 / count as u64) as u32;
+        let end =
+            (image_height as u64 * (index as u64 + 1) / count as u64) as u32;
+        Some(Self::new(0, start, image_width, end - start))
     }
 
-    impl From<&Image> for RgbaImage {
-        fn from(val: &Image) -> Self {
-            RgbaImage::from_vec(val.width, val.height, val.pixel_data.clone())
-                .expect("Failed to create image buffer from vector")
+    /// Returns the part of the region inside an image of the given size.
+    pub fn clamp(&self, image_width: u32, image_height: u32) -> Self {
+        let x = self.x.min(image_width);
+        let y = self.y.min(image_height);
+        Self {
+            x,
+            y,
+            width: self.width.min(image_width - x),
+            height: self.height.min(image_height - y),
         }
     }
+}
 
-    impl From<Image> for RgbaImage {
-        fn from(val: Image) -> Self {
-            RgbaImage::from(&val)
-        }
+impl fmt::Display for RenderRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.x, self.y, self.width, self.height)
     }
+}
 
-    pub fn save_to_file<P: AsRef<std::path::Path>>(
-        image: &Image,
-        path: P,
-        format: image::ImageFormat,
-    ) -> Result<(), ImageError> {
-        // let buffer: image::RgbaImage = ImageBuffer::from_vec(
-        //     image.width,
-        //     image.height,
-        //     image.pixel_data.clone(),
-        // )
-        // .expect("Failed to create image buffer from vector");
-        RgbaImage::from(image).save_with_format(path, format)
+impl FromStr for RenderRegion {
+    type Err = String;
+
+    /// Parses `x,y,width,height`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let values = value
+            .split(',')
+            .map(|part| part.trim().parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| format!("Invalid render region '{}'", value))?;
+
+        match values[..] {
+            [x, y, width, height] => Ok(Self::new(x, y, width, height)),
+            _ => Err(format!(
+                "Render region '{}' must be x,y,width,height",
+                value
+            )),
+        }
     }
 }
 
@@ -124,9 +103,28 @@ pub struct RenderOptions {
     pub aspect_ratio: f64,
     pub samples_per_pixel: u16,
     pub max_ray_depth: u16,
+    /// Bounce count after which paths are terminated by russian roulette.
+    pub russian_roulette_min_depth: u16,
+    pub integrator: IntegratorType,
+    /// Traces one wavelength per sample instead of RGB, enabling dispersion.
+    pub spectral: bool,
+    /// Renders only the pixels inside the region. The rest of the image stays
+    /// transparent unless `crop_to_region` is set.
+    pub region: Option<RenderRegion>,
+    /// Returns an image of the region's size instead of the full image.
+    pub crop_to_region: bool,
+    pub filter: FilterType,
+    /// Filter radius in pixels.
+    pub filter_radius: f64,
+    /// Index of the first sample, which seeds the random numbers. Renders
+    /// of the same region with different offsets can be merged into one
+    /// with more samples.
+    pub sample_offset: u32,
 }
 
 impl RenderOptions {
+    pub const DEFAULT_RUSSIAN_ROULETTE_MIN_DEPTH: u16 = 3;
+
     pub fn new(
         image_width: u32,
         aspect_ratio: f64,
@@ -138,7 +136,77 @@ impl RenderOptions {
             aspect_ratio,
             samples_per_pixel,
             max_ray_depth,
+            russian_roulette_min_depth:
+                Self::DEFAULT_RUSSIAN_ROULETTE_MIN_DEPTH,
+            integrator: IntegratorType::default(),
+            spectral: false,
+            region: None,
+            crop_to_region: false,
+            filter: FilterType::default(),
+            filter_radius: FilterType::default().default_radius(),
+            sample_offset: 0,
+        }
+    }
+
+    /// The render region inside the image, or the whole image.
+    pub fn clamped_region(&self) -> RenderRegion {
+        let (width, height) = (self.image_width, self.image_height());
+        self.region
+            .unwrap_or(RenderRegion::new(0, 0, width, height))
+            .clamp(width, height)
+    }
+
+    /// Height of the rendered image in pixels.
+    pub fn image_height(&self) -> u32 {
+        (self.image_width as f64 / self.aspect_ratio) as u32
+    }
+
+    /// Bytes of the options that change the color of a pixel's samples,
+    /// which are all but the region, the sample count and the sample
+    /// offset. They are hashed to tell whether checkpoints and partial
+    /// buffers belong to the same image, so each field is written
+    /// explicitly instead of relying on a format that may change.
+    pub(crate) fn sample_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(self.image_width.to_le_bytes());
+        bytes.extend(self.aspect_ratio.to_le_bytes());
+        bytes.extend(self.max_ray_depth.to_le_bytes());
+        bytes.extend(self.russian_roulette_min_depth.to_le_bytes());
+        bytes.push(self.integrator as u8);
+        bytes.push(self.spectral as u8);
+        bytes.push(self.filter as u8);
+        bytes.extend(self.filter_radius.to_le_bytes());
+        bytes
+    }
+
+    /// Checks that the options describe a non-empty image that can be
+    /// sampled.
+    pub fn validate(&self) -> error::Result<()> {
+        if self.image_width == 0 {
+            return Err(Error::InvalidImageWidth(self.image_width));
+        }
+        if !(self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0) {
+            return Err(Error::InvalidAspectRatio(self.aspect_ratio));
+        }
+        if self.image_height() == 0 {
+            return Err(Error::InvalidImageHeight {
+                image_width: self.image_width,
+                aspect_ratio: self.aspect_ratio,
+            });
+        }
+        if self.samples_per_pixel == 0 {
+            return Err(Error::InvalidSamplesPerPixel(self.samples_per_pixel));
         }
+        if !(self.filter_radius.is_finite() && self.filter_radius > 0.0) {
+            return Err(Error::InvalidFilterRadius(self.filter_radius));
+        }
+        if let Some(region) = self.region {
+            let clamped = region.clamp(self.image_width, self.image_height());
+            if clamped.width == 0 || clamped.height == 0 {
+                return Err(Error::EmptyRenderRegion(region));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -146,39 +214,175 @@ impl fmt::Display for RenderOptions {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "RenderOptions {{ image_width: {}, aspect_ratio: {:.2}, samples_per_pixel: {}, max_ray_depth: {} }}",
+            "RenderOptions {{ image_width: {}, aspect_ratio: {:.2}, samples_per_pixel: {}, max_ray_depth: {}, russian_roulette_min_depth: {}, integrator: {}, spectral: {}, region: {}, crop_to_region: {}, filter: {}, filter_radius: {:.2}, sample_offset: {} }}",
             self.image_width,
             self.aspect_ratio,
             self.samples_per_pixel,
-            self.max_ray_depth
+            self.max_ray_depth,
+            self.russian_roulette_min_depth,
+            self.integrator,
+            self.spectral,
+            self.region
+                .map_or("none".to_string(), |region| region.to_string()),
+            self.crop_to_region,
+            self.filter,
+            self.filter_radius,
+            self.sample_offset
         )
     }
 }
 
+/// Image returned by `render_image` together with the statistics of the
+/// render.
+pub struct RenderedImage {
+    pub image: image::Image,
+    pub stats: RenderStats,
+}
+
+/// Partial buffer returned by `try_render_partial` together with the
+/// statistics of the render.
+pub struct RenderedPartial {
+    pub partial: PartialBuffer,
+    pub stats: RenderStats,
+}
+
 pub struct Raytracer {
-    background_gradient_start: Color,
-    background_gradient_end: Color,
     raytracer_context: RaytracerContext,
-    objects: Vec<Box<dyn RayHitTest>>,
+    scene: Scene,
+    checkpoint: Option<CheckpointSettings>,
+    progress: Option<RenderProgress>,
 }
 
 impl Raytracer {
     pub fn new() -> Self {
         Self {
-            background_gradient_start: Color::new(0.3, 0.6, 0.9),
-            background_gradient_end: Color::new(1.0, 1.0, 1.0),
             raytracer_context: RaytracerContext::new(),
-            objects: Vec::new(),
+            scene: Scene::default(),
+            checkpoint: None,
+            progress: None,
         }
     }
 
+    /// Saves the progress of renders periodically, and continues from a
+    /// saved checkpoint if the settings ask to resume. The last checkpoint
+    /// is kept after the render, see `CheckpointSettings::remove`.
+    pub fn with_checkpoint(mut self, checkpoint: CheckpointSettings) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Reports the completed samples and previews of renders to the
+    /// progress handle, and stops them once it is cancelled.
+    pub fn with_progress(mut self, progress: RenderProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Renders the image, see `try_render_image`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the options or the camera are invalid or the image cannot
+    /// be allocated.
     pub fn render_image(
         &mut self,
         camera: &Camera,
         objects: Vec<Box<dyn RayHitTest>>,
         options: &RenderOptions,
-    ) -> image::Image {
-        self.objects = objects;
+    ) -> RenderedImage {
+        match self.try_render_image(camera, objects, options) {
+            Ok(rendered_image) => rendered_image,
+            Err(error) => panic!("Unable to render image: {}", error),
+        }
+    }
+
+    /// Renders the image after validating the options and the camera. The
+    /// buffers are allocated before tracing any ray.
+    pub fn try_render_image(
+        &mut self,
+        camera: &Camera,
+        objects: Vec<Box<dyn RayHitTest>>,
+        options: &RenderOptions,
+    ) -> error::Result<RenderedImage> {
+        options.validate()?;
+        camera.validate()?;
+
+        let region = options.clamped_region();
+        let mut rgb_image = new_image(options)?;
+
+        let (accumulation_buffer, stats) = self.accumulate(
+            camera,
+            objects,
+            options,
+            region,
+            options.sample_offset != 0,
+        )?;
+        resolve_image(
+            &accumulation_buffer,
+            options,
+            camera.exposure,
+            &mut rgb_image,
+        );
+
+        Ok(RenderedImage {
+            image: rgb_image,
+            stats,
+        })
+    }
+
+    /// Renders the region of the options into a partial buffer, which can be
+    /// merged with the buffers of other regions or sample ranges of the
+    /// scene identified by `scene_hash`, see `CheckpointSettings::hash`.
+    pub fn try_render_partial(
+        &mut self,
+        camera: &Camera,
+        objects: Vec<Box<dyn RayHitTest>>,
+        options: &RenderOptions,
+        scene_hash: u64,
+    ) -> error::Result<RenderedPartial> {
+        options.validate()?;
+        camera.validate()?;
+
+        let region = options.clamped_region();
+        let (accumulation_buffer, stats) =
+            self.accumulate(camera, objects, options, region, true)?;
+
+        Ok(RenderedPartial {
+            partial: PartialBuffer::new(
+                options,
+                scene_hash,
+                camera.exposure,
+                accumulation_buffer,
+            ),
+            stats,
+        })
+    }
+
+    /// Traces the samples of the region and splats them into an
+    /// accumulation buffer. With `seed_samples` the random numbers only
+    /// depend on the sample offset, so that separately rendered sample
+    /// ranges don't repeat each other.
+    fn accumulate(
+        &mut self,
+        camera: &Camera,
+        objects: Vec<Box<dyn RayHitTest>>,
+        options: &RenderOptions,
+        region: RenderRegion,
+        seed_samples: bool,
+    ) -> error::Result<(AccumulationBuffer, RenderStats)> {
+        let start_time = Instant::now();
+        let start_cpu_time = stats::process_cpu_time();
+        let mut stats = RenderStats::default();
+
+        self.scene.set_objects(objects);
+        let integrator = options.integrator.create_integrator();
+        let spectral = options.spectral && integrator.supports_spectral();
+        if options.spectral && !spectral {
+            log::warn!(
+                "Integrator {} does not support spectral rendering, falling back to RGB",
+                options.integrator
+            );
+        }
 
         self.raytracer_context.update_viewport(
             options.image_width,
@@ -186,82 +390,249 @@ impl Raytracer {
             camera,
         );
 
-        let pixel_samples_scale =
-            Self::pixel_samples_scale(options.samples_per_pixel);
+        let chromatic_aberration =
+            self.raytracer_context.has_chromatic_aberration();
 
         log::info!("Rendering image with {}", options);
 
-        let mut rgb_image = image::Image::new(
-            self.raytracer_context.image_width(),
-            self.raytracer_context.image_height(),
-        );
-
-        for y in 0..self.raytracer_context.image_height() {
-            for x in 0..self.raytracer_context.image_width() {
-                let mut color = Color::new(0.0, 0.0, 0.0);
+        // Samples outside of the region within the filter radius contribute
+        // to its border pixels, so that adjacent regions fit seamlessly.
+        // Margins beyond the image size are clamped away anyway, which keeps
+        // huge radii from overflowing the region size.
+        let image_width = self.raytracer_context.image_width();
+        let image_height = self.raytracer_context.image_height();
+        let margin = ((options.filter_radius - 0.5).ceil().max(0.0) as u32)
+            .min(image_width.max(image_height));
+        let sampled_region = RenderRegion::new(
+            region.x.saturating_sub(margin),
+            region.y.saturating_sub(margin),
+            region.width.saturating_add(margin.saturating_mul(2)),
+            region.height.saturating_add(margin.saturating_mul(2)),
+        )
+        .clamp(image_width, image_height);
 
-                for _sample in 0..options.samples_per_pixel {
-                    let ray = &self.raytracer_context.get_ray(x, y);
-                    color += self.calculate_color(ray, options.max_ray_depth);
-                }
+        let mut accumulation_buffer = AccumulationBuffer::new(
+            region,
+            options.filter,
+            options.filter_radius,
+        )?;
 
-                let color1 = color * pixel_samples_scale;
-                rgb_image.set_pixel(x, y, color1.as_rgba());
+        if seed_samples {
+            random::set_state(random::sample_seed(options.sample_offset));
+        }
+        let mut first_sample = 0;
+        if let Some(settings) = &self.checkpoint
+            && settings.resume
+        {
+            if settings.path.is_file() {
+                let state = checkpoint::load(
+                    settings,
+                    options,
+                    &mut accumulation_buffer,
+                )?;
+                random::set_state(state.rng_state);
+                first_sample = state.completed_samples;
+                log::info!(
+                    "Resuming from {:?} after {} samples per pixel",
+                    settings.path,
+                    first_sample
+                );
+            } else {
+                log::info!(
+                    "No checkpoint {:?} to resume from, starting a new render",
+                    settings.path
+                );
             }
         }
-
-        rgb_image
-    }
-
-    fn calculate_color(&mut self, ray: &Ray, depth: u16) -> Color {
-        if depth == 0 {
-            return Color::new(0.0, 0.0, 0.0);
+        let mut last_checkpoint = Instant::now();
+        let mut last_preview = None::<Instant>;
+        if let Some(progress) = &self.progress {
+            progress.start(first_sample, options.samples_per_pixel);
         }
 
-        if let Some(ray_hit) =
-            self.does_hit_object(ray, &Interval::new(0.001, f64::INFINITY))
-        {
-            match ray_hit.material.scatter(
-                ray,
-                ray_hit.point(),
-                ray_hit.normal(),
-                ray_hit.is_front_face(),
-            ) {
-                Some(result) => {
-                    return result.1
-                        * self.calculate_color(&result.0, depth - 1);
+        // Every pass adds one sample to each pixel, so checkpoints between
+        // passes hold the same sample count for all pixels.
+        for sample in first_sample..options.samples_per_pixel {
+            for y in sampled_region.y..sampled_region.y + sampled_region.height
+            {
+                if let Some(progress) = &self.progress
+                    && progress.is_cancelled()
+                {
+                    log::info!("Render cancelled after {} samples", sample);
+                    return Err(Error::RenderCancelled);
+                }
+                for x in
+                    sampled_region.x..sampled_region.x + sampled_region.width
+                {
+                    // Uniformly distributed inside the pixel, whose center
+                    // lies at the integer position.
+                    let sample_x = x as f64 + random::random_f64_0_1() - 0.5;
+                    let sample_y = y as f64 + random::random_f64_0_1() - 0.5;
+
+                    let rays_before = self.scene.counters().rays;
+                    let color = match self.sample_color(
+                        integrator.as_ref(),
+                        (sample_x, sample_y),
+                        spectral,
+                        chromatic_aberration,
+                        options,
+                    ) {
+                        Some(color) => {
+                            stats.primary_rays += 1;
+                            color
+                        }
+                        None => Color::new(0.0, 0.0, 0.0),
+                    };
+                    stats.record_path_length(
+                        self.scene.counters().rays - rays_before,
+                    );
+
+                    if !color.is_finite() {
+                        stats.invalid_samples += 1;
+                        continue;
+                    }
+                    accumulation_buffer.splat(sample_x, sample_y, color);
                 }
-                None => return Color::new(0.0, 0.0, 0.0),
             }
-        }
 
-        let a: f64 = (ray.direction().unit().y() + 1.0) * 0.5;
-        self.background_gradient_end * (1.0 - a)
-            + self.background_gradient_start * a
-    }
+            let completed_samples = sample + 1;
+            if let Some(progress) = &self.progress {
+                progress.set_completed_samples(completed_samples);
+                if let Some(interval) = progress.preview_interval()
+                    && completed_samples < options.samples_per_pixel
+                    && last_preview
+                        .is_none_or(|last: Instant| last.elapsed() >= interval)
+                {
+                    let mut preview = new_image(options)?;
+                    resolve_image(
+                        &accumulation_buffer,
+                        options,
+                        camera.exposure,
+                        &mut preview,
+                    );
+                    progress.set_preview(preview);
+                    last_preview = Some(Instant::now());
+                }
+            }
+            if let Some(settings) = &self.checkpoint
+                && completed_samples < options.samples_per_pixel
+                && last_checkpoint.elapsed() >= settings.interval
+            {
+                let state = CheckpointState {
+                    completed_samples,
+                    rng_state: random::state(),
+                };
+                match checkpoint::save(
+                    settings,
+                    options,
+                    state,
+                    &accumulation_buffer,
+                ) {
+                    Ok(()) => log::info!(
+                        "Saved checkpoint {:?} after {} samples per pixel",
+                        settings.path,
+                        completed_samples
+                    ),
+                    Err(error) => log::warn!(
+                        "Unable to save checkpoint {:?}: {}",
+                        settings.path,
+                        error
+                    ),
+                }
+                last_checkpoint = Instant::now();
+            }
+        }
+        let counters = self.scene.counters();
+        stats.secondary_rays = counters.rays - stats.primary_rays;
+        stats.object_tests = counters.object_tests;
+        stats.wall_time = start_time.elapsed();
+        stats.cpu_time = start_cpu_time
+            .zip(stats::process_cpu_time())
+            .map(|(start, end)| end.saturating_sub(start));
+        log::info!(
+            "Rendered image in {:.3} s, {:.0} rays/s",
+            stats.wall_time.as_secs_f64(),
+            stats.rays_per_second()
+        );
 
-    fn pixel_samples_scale(samples_per_pixel: u16) -> f64 {
-        1.0 / (samples_per_pixel as f64)
+        Ok((accumulation_buffer, stats))
     }
 
-    fn does_hit_object(
+    /// Traces a single camera sample at the image position. Returns `None`
+    /// for samples blocked by the camera.
+    fn sample_color(
         &mut self,
-        ray: &Ray,
-        ray_t: &crate::math::interval::Interval,
-    ) -> Option<crate::ray_hit::RayHitDetails> {
-        let mut hit_result: Option<RayHitDetails> = None;
-        let mut closest = ray_t.max();
-
-        for object in self.objects.iter_mut() {
-            if let Some(hit) =
-                object.does_hit(ray, &Interval::new(ray_t.min(), closest))
-            {
-                closest = hit.t();
-                hit_result = Some(hit);
-            }
+        integrator: &dyn Integrator,
+        (x, y): (f64, f64),
+        spectral: bool,
+        chromatic_aberration: bool,
+        options: &RenderOptions,
+    ) -> Option<Color> {
+        if spectral {
+            let wavelength = spectrum::sample_wavelength();
+            let ray = self.raytracer_context.get_ray(x, y, Some(wavelength))?;
+            let radiance = integrator.calculate_color(
+                &mut self.scene,
+                &ray.with_wavelength(Some(wavelength)),
+                options,
+            );
+            Some(spectrum::wavelength_to_rgb(radiance.r(), wavelength))
+        } else if chromatic_aberration {
+            // Traces a single channel, shifted by the wavelength it is
+            // centered on.
+            let channel = random::random_usize(0..3);
+            let ray = self.raytracer_context.get_ray(
+                x,
+                y,
+                Some(spectrum::UPLIFT_CENTERS[channel]),
+            )?;
+            let mut weight = [0.0; 3];
+            weight[channel] = 3.0;
+            Some(
+                integrator.calculate_color(&mut self.scene, &ray, options)
+                    * Color::from(weight),
+            )
+        } else {
+            let ray = self.raytracer_context.get_ray(x, y, None)?;
+            Some(integrator.calculate_color(&mut self.scene, &ray, options))
         }
+    }
+}
 
-        hit_result
+/// Allocates the full size image, or one of the region size if the options
+/// crop to it.
+fn new_image(options: &RenderOptions) -> error::Result<image::Image> {
+    if options.crop_to_region {
+        let region = options.clamped_region();
+        image::Image::try_new(region.width, region.height)
+    } else {
+        image::Image::try_new(options.image_width, options.image_height())
+    }
+}
+
+/// Writes the exposed colors of the accumulated region into the image, which
+/// is either of full size or cropped to the region.
+fn resolve_image(
+    accumulation_buffer: &AccumulationBuffer,
+    options: &RenderOptions,
+    exposure: f64,
+    image: &mut image::Image,
+) {
+    let region = accumulation_buffer.region();
+    let (image_offset_x, image_offset_y) = if options.crop_to_region {
+        (region.x, region.y)
+    } else {
+        (0, 0)
+    };
+    for y in region.y..region.y + region.height {
+        for x in region.x..region.x + region.width {
+            let color = accumulation_buffer.resolve(x, y) * exposure;
+            image.set_pixel(
+                x - image_offset_x,
+                y - image_offset_y,
+                color.as_rgba(),
+            );
+        }
     }
 }
 
@@ -270,3 +641,186 @@ impl Default for Raytracer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn render_region_from_str_should_parse_rectangle() {
+        assert_eq!(
+            RenderRegion::from_str("10, 20,30,40"),
+            Ok(RenderRegion::new(10, 20, 30, 40))
+        );
+        assert!(RenderRegion::from_str("10,20,30").is_err());
+        assert!(RenderRegion::from_str("a,b,c,d").is_err());
+    }
+
+    #[test]
+    fn render_region_clamp_should_stay_inside_image() {
+        assert_eq!(
+            RenderRegion::new(50, 80, 100, 100).clamp(100, 100),
+            RenderRegion::new(50, 80, 50, 20)
+        );
+        assert_eq!(
+            RenderRegion::new(150, 0, 10, 10).clamp(100, 100),
+            RenderRegion::new(100, 0, 0, 10)
+        );
+    }
+
+    #[test]
+    fn render_image_should_crop_to_region() {
+        let mut options = RenderOptions::new(40, 2.0, 1, 1);
+        options.region = Some(RenderRegion::new(5, 5, 10, 4));
+
+        let mut raytracer = Raytracer::new();
+        let full = raytracer
+            .render_image(&Camera::default(), vec![], &options)
+            .image;
+        assert_eq!((full.width(), full.height()), (40, 20));
+        // Pixels outside of the region stay transparent.
+        assert_eq!(full.data()[3], 0);
+
+        options.crop_to_region = true;
+        let cropped = raytracer
+            .render_image(&Camera::default(), vec![], &options)
+            .image;
+        assert_eq!((cropped.width(), cropped.height()), (10, 4));
+        assert_eq!(cropped.data()[3], 255);
+    }
+
+    #[test]
+    fn render_image_should_count_primary_rays() {
+        let options = RenderOptions::new(8, 2.0, 3, 1);
+
+        let stats = Raytracer::new()
+            .render_image(&Camera::default(), vec![], &options)
+            .stats;
+
+        assert_eq!(stats.primary_rays, 8 * 4 * 3);
+        assert_eq!(stats.secondary_rays, 0);
+        assert_eq!(stats.path_length_histogram, vec![0, 8 * 4 * 3]);
+        assert_eq!(stats.invalid_samples, 0);
+    }
+
+    #[test]
+    fn render_image_should_report_progress_and_previews() {
+        let options = RenderOptions::new(8, 2.0, 3, 1);
+        let progress =
+            RenderProgress::new().with_preview_interval(Duration::ZERO);
+
+        Raytracer::new()
+            .with_progress(progress.clone())
+            .render_image(&Camera::default(), vec![], &options);
+
+        assert_eq!(progress.completed_samples(), 3);
+        assert_eq!(progress.fraction(), 1.0);
+        let preview = progress.preview().unwrap();
+        assert_eq!((preview.width(), preview.height()), (8, 4));
+    }
+
+    #[test]
+    fn try_render_image_should_clamp_margin_of_huge_filter_radius() {
+        let mut options = RenderOptions::new(8, 2.0, 1, 1);
+        options.region = Some(RenderRegion::new(2, 1, 4, 2));
+        options.filter_radius = 1e10;
+
+        let rendered = Raytracer::new()
+            .try_render_image(&Camera::default(), vec![], &options)
+            .unwrap();
+
+        assert_eq!(rendered.stats.primary_rays, 8 * 4);
+    }
+
+    #[test]
+    fn try_render_image_should_stop_when_cancelled() {
+        let progress = RenderProgress::new();
+        progress.cancel();
+
+        let result = Raytracer::new()
+            .with_progress(progress.clone())
+            .try_render_image(
+                &Camera::default(),
+                vec![],
+                &RenderOptions::new(8, 2.0, 3, 1),
+            );
+
+        assert_eq!(result.err(), Some(Error::RenderCancelled));
+        assert_eq!(progress.completed_samples(), 0);
+    }
+
+    #[test]
+    fn tile_should_split_image_into_strips() {
+        let tiles = (0..3)
+            .map(|index| RenderRegion::tile(8, 10, index, 3).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tiles,
+            vec![
+                RenderRegion::new(0, 0, 8, 3),
+                RenderRegion::new(0, 3, 8, 3),
+                RenderRegion::new(0, 6, 8, 4),
+            ]
+        );
+        assert_eq!(RenderRegion::tile(8, 10, 3, 3), None);
+        assert_eq!(RenderRegion::tile(8, 2, 0, 3), None);
+    }
+
+    #[test]
+    fn validate_should_reject_invalid_options() {
+        assert_eq!(RenderOptions::new(8, 2.0, 1, 1).validate(), Ok(()));
+        assert_eq!(
+            RenderOptions::new(0, 2.0, 1, 1).validate(),
+            Err(Error::InvalidImageWidth(0))
+        );
+        assert_eq!(
+            RenderOptions::new(8, 0.0, 1, 1).validate(),
+            Err(Error::InvalidAspectRatio(0.0))
+        );
+        assert_eq!(
+            RenderOptions::new(8, 16.0, 1, 1).validate(),
+            Err(Error::InvalidImageHeight {
+                image_width: 8,
+                aspect_ratio: 16.0
+            })
+        );
+        assert_eq!(
+            RenderOptions::new(8, 2.0, 0, 1).validate(),
+            Err(Error::InvalidSamplesPerPixel(0))
+        );
+
+        let mut options = RenderOptions::new(8, 2.0, 1, 1);
+        options.region = Some(RenderRegion::new(8, 0, 2, 2));
+        assert_eq!(
+            options.validate(),
+            Err(Error::EmptyRenderRegion(RenderRegion::new(8, 0, 2, 2)))
+        );
+    }
+
+    #[test]
+    fn try_render_image_should_reject_degenerate_camera() {
+        let mut camera = Camera::default();
+        camera.look_at = camera.position;
+
+        let result = Raytracer::new().try_render_image(
+            &camera,
+            vec![],
+            &RenderOptions::new(8, 2.0, 1, 1),
+        );
+
+        assert_eq!(result.err(), Some(Error::CameraLooksAtItself));
+    }
+
+    #[test]
+    fn try_new_should_fail_for_overflowing_size() {
+        assert_eq!(
+            image::Image::<u8>::try_new(u32::MAX, u32::MAX).err(),
+            Some(Error::ImageAllocation {
+                width: u32::MAX,
+                height: u32::MAX
+            })
+        );
+    }
+}
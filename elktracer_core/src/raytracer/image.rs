@@ -0,0 +1,581 @@
+use image::{
+    DynamicImage, EncodableLayout, ExtendedColorType, GenericImageView,
+    ImageBuffer, ImageError, Pixel, Primitive,
+    imageops::{self, FilterType},
+};
+
+use crate::{
+    error::{self, Error},
+    raytracer::RenderRegion,
+    utils::try_filled_vec,
+};
+
+/// Subpixel type of an `Image`, with 0 as black and `MAX` as full
+/// intensity.
+pub trait Channel:
+    Primitive + Default + std::fmt::Debug + Send + Sync + 'static
+{
+    /// Pixel type of the image crate with RGBA channels of this type.
+    type RgbaPixel: Pixel<Subpixel = Self>;
+
+    const MAX: Self;
+    const COLOR_TYPE: ExtendedColorType;
+
+    /// Converts from the nominal range 0..=1.
+    fn from_unit(value: f32) -> Self;
+    fn to_unit(self) -> f32;
+    fn slice_as_bytes(slice: &[Self]) -> &[u8];
+    fn into_dynamic_image(
+        buffer: ImageBuffer<Self::RgbaPixel, Vec<Self>>,
+    ) -> DynamicImage;
+    fn from_dynamic_image(
+        image: DynamicImage,
+    ) -> ImageBuffer<Self::RgbaPixel, Vec<Self>>;
+}
+
+impl Channel for u8 {
+    type RgbaPixel = image::Rgba<u8>;
+
+    const MAX: Self = u8::MAX;
+    const COLOR_TYPE: ExtendedColorType = ExtendedColorType::Rgba8;
+
+    fn from_unit(value: f32) -> Self {
+        (value.clamp(0.0, 1.0) * Self::MAX as f32).round() as Self
+    }
+
+    fn to_unit(self) -> f32 {
+        self as f32 / Self::MAX as f32
+    }
+
+    fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+        slice
+    }
+
+    fn into_dynamic_image(
+        buffer: ImageBuffer<Self::RgbaPixel, Vec<Self>>,
+    ) -> DynamicImage {
+        DynamicImage::ImageRgba8(buffer)
+    }
+
+    fn from_dynamic_image(
+        image: DynamicImage,
+    ) -> ImageBuffer<Self::RgbaPixel, Vec<Self>> {
+        image.into_rgba8()
+    }
+}
+
+impl Channel for u16 {
+    type RgbaPixel = image::Rgba<u16>;
+
+    const MAX: Self = u16::MAX;
+    const COLOR_TYPE: ExtendedColorType = ExtendedColorType::Rgba16;
+
+    fn from_unit(value: f32) -> Self {
+        (value.clamp(0.0, 1.0) * Self::MAX as f32).round() as Self
+    }
+
+    fn to_unit(self) -> f32 {
+        self as f32 / Self::MAX as f32
+    }
+
+    fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+        slice.as_bytes()
+    }
+
+    fn into_dynamic_image(
+        buffer: ImageBuffer<Self::RgbaPixel, Vec<Self>>,
+    ) -> DynamicImage {
+        DynamicImage::ImageRgba16(buffer)
+    }
+
+    fn from_dynamic_image(
+        image: DynamicImage,
+    ) -> ImageBuffer<Self::RgbaPixel, Vec<Self>> {
+        image.into_rgba16()
+    }
+}
+
+/// Linear values, which may exceed 1 for high dynamic range images.
+impl Channel for f32 {
+    type RgbaPixel = image::Rgba<f32>;
+
+    const MAX: Self = 1.0;
+    const COLOR_TYPE: ExtendedColorType = ExtendedColorType::Rgba32F;
+
+    fn from_unit(value: f32) -> Self {
+        value
+    }
+
+    fn to_unit(self) -> f32 {
+        self
+    }
+
+    fn slice_as_bytes(slice: &[Self]) -> &[u8] {
+        slice.as_bytes()
+    }
+
+    fn into_dynamic_image(
+        buffer: ImageBuffer<Self::RgbaPixel, Vec<Self>>,
+    ) -> DynamicImage {
+        DynamicImage::ImageRgba32F(buffer)
+    }
+
+    fn from_dynamic_image(
+        image: DynamicImage,
+    ) -> ImageBuffer<Self::RgbaPixel, Vec<Self>> {
+        image.into_rgba32f()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba<T = u8> {
+    r: T,
+    g: T,
+    b: T,
+    a: T,
+}
+
+impl<T: Copy> Rgba<T> {
+    pub fn new(r: T, g: T, b: T, a: T) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn r(&self) -> T {
+        self.r
+    }
+
+    pub fn g(&self) -> T {
+        self.g
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+
+    pub fn a(&self) -> T {
+        self.a
+    }
+}
+
+/// RGBA image with interleaved channels, stored row by row from the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image<T = u8> {
+    width: u32,
+    height: u32,
+    pixel_data: Vec<T>,
+    channels: u32,
+}
+
+pub type Image16 = Image<u16>;
+pub type Image32F = Image<f32>;
+
+impl<T: Channel> Image<T> {
+    /// Creates a transparent image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pixel data cannot be allocated, see `try_new`.
+    pub fn new(width: u32, height: u32) -> Self {
+        match Self::try_new(width, height) {
+            Ok(image) => image,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Creates a transparent image, failing if the size of the pixel data
+    /// overflows or the memory is not available.
+    pub fn try_new(width: u32, height: u32) -> error::Result<Self> {
+        let channels = 4;
+        let allocation_error = Error::ImageAllocation { width, height };
+        let pixel_data_length = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(channels as usize))
+            .ok_or(allocation_error.clone())?;
+        log::trace!(
+            "Image :: new :: {}x{} :: {} channels :: pixel_data [{}]",
+            width,
+            height,
+            channels,
+            pixel_data_length
+        );
+        Ok(Self {
+            width,
+            height,
+            pixel_data: try_filled_vec(pixel_data_length, T::default())
+                .ok_or(allocation_error)?,
+            channels,
+        })
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: Rgba<T>) {
+        if x >= self.width || y >= self.height {
+            log::warn!(
+                "Pixel coordinated out of bounds. Given x={} and y={}, image width={} and height={}. Pixel data not updated",
+                x,
+                y,
+                self.width,
+                self.height
+            );
+            return;
+        }
+
+        let index = self.index(x, y);
+        self.pixel_data[index] = rgba.r;
+        self.pixel_data[index + 1] = rgba.g;
+        self.pixel_data[index + 2] = rgba.b;
+        self.pixel_data[index + 3] = rgba.a;
+    }
+
+    /// Returns the pixel, or `None` if the position is outside the image.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Rgba<T>> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let index = self.index(x, y);
+        Some(Rgba::new(
+            self.pixel_data[index],
+            self.pixel_data[index + 1],
+            self.pixel_data[index + 2],
+            self.pixel_data[index + 3],
+        ))
+    }
+
+    /// Returns a copy of the pixel data, see `as_slice` for borrowing it.
+    pub fn data(&self) -> Vec<T> {
+        self.pixel_data.to_vec()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.pixel_data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.pixel_data
+    }
+
+    /// Pixel data in native byte order.
+    pub fn as_bytes(&self) -> &[u8] {
+        T::slice_as_bytes(&self.pixel_data)
+    }
+
+    pub fn into_raw(self) -> Vec<T> {
+        self.pixel_data
+    }
+
+    /// Iterates over the rows from the top, each holding the interleaved
+    /// channels of `width` pixels. An image without width has no rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.pixel_data.chunks_exact(self.chunk_length())
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let chunk_length = self.chunk_length();
+        self.pixel_data.chunks_exact_mut(chunk_length)
+    }
+
+    /// Borrows the rectangle of the image, or returns `None` if the region
+    /// is empty or not fully inside the image.
+    pub fn view(&self, region: RenderRegion) -> Option<ImageView<'_, T>> {
+        if region.width == 0
+            || region.height == 0
+            || region.clamp(self.width, self.height) != region
+        {
+            return None;
+        }
+        Some(ImageView {
+            image: self,
+            region,
+        })
+    }
+
+    /// Converts the channels through their nominal range. Converting
+    /// to a smaller type clamps values above it.
+    pub fn convert<U: Channel>(&self) -> Image<U> {
+        Image {
+            width: self.width,
+            height: self.height,
+            pixel_data: self
+                .pixel_data
+                .iter()
+                .map(|value| U::from_unit(value.to_unit()))
+                .collect(),
+            channels: self.channels,
+        }
+    }
+
+    /// Averages blocks of `factor` x `factor` pixels. Partial blocks at the
+    /// right and bottom edges are averaged over the pixels they contain.
+    pub fn downsample(&self, factor: u32) -> Self {
+        let factor = factor.max(1);
+        let width = self.width.div_ceil(factor);
+        let height = self.height.div_ceil(factor);
+        let mut downsampled = Self::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0.0; 4];
+                let mut count = 0.0;
+                for source_y in y * factor..((y + 1) * factor).min(self.height)
+                {
+                    for source_x in
+                        x * factor..((x + 1) * factor).min(self.width)
+                    {
+                        let index = self.index(source_x, source_y);
+                        for (channel, value) in sum.iter_mut().enumerate() {
+                            *value +=
+                                self.pixel_data[index + channel].to_unit();
+                        }
+                        count += 1.0;
+                    }
+                }
+
+                let index = downsampled.index(x, y);
+                for (channel, value) in sum.iter().enumerate() {
+                    downsampled.pixel_data[index + channel] =
+                        T::from_unit(value / count);
+                }
+            }
+        }
+        downsampled
+    }
+
+    /// Resamples the image to the given size with a filter of the image
+    /// crate.
+    pub fn resize(&self, width: u32, height: u32, filter: FilterType) -> Self {
+        Self::from(imageops::resize(self, width, height, filter))
+    }
+
+    /// Length of the rows for chunking the pixel data, which is empty if
+    /// the row length is zero.
+    fn chunk_length(&self) -> usize {
+        ((self.width * self.channels) as usize).max(1)
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * self.channels as usize
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// Allows using the image with `image::imageops` without copying it.
+impl<T: Channel> GenericImageView for Image<T> {
+    type Pixel = T::RgbaPixel;
+
+    fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Self::Pixel {
+        let index = self.index(x, y);
+        *T::RgbaPixel::from_slice(&self.pixel_data[index..index + 4])
+    }
+}
+
+/// Borrowed rectangle of an `Image`.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageView<'a, T = u8> {
+    image: &'a Image<T>,
+    region: RenderRegion,
+}
+
+impl<T: Channel> ImageView<'_, T> {
+    /// Returns the pixel at the position relative to the view, or `None` if
+    /// it lies outside of the view.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Rgba<T>> {
+        if x >= self.region.width || y >= self.region.height {
+            return None;
+        }
+        self.image.get_pixel(self.region.x + x, self.region.y + y)
+    }
+
+    /// Iterates over the rows of the view, each holding the interleaved
+    /// channels of `width` pixels.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        let start = (self.region.x * self.image.channels) as usize;
+        let end = start + (self.region.width * self.image.channels) as usize;
+        self.image
+            .rows()
+            .skip(self.region.y as usize)
+            .take(self.region.height as usize)
+            .map(move |row| &row[start..end])
+    }
+
+    /// Copies the view into an image of its size.
+    pub fn to_image(&self) -> Image<T> {
+        Image {
+            width: self.region.width,
+            height: self.region.height,
+            pixel_data: self.rows().flatten().copied().collect(),
+            channels: self.image.channels,
+        }
+    }
+
+    pub fn region(&self) -> RenderRegion {
+        self.region
+    }
+
+    pub fn width(&self) -> u32 {
+        self.region.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.region.height
+    }
+}
+
+impl<T: Channel> TryFrom<&Image<T>> for ImageBuffer<T::RgbaPixel, Vec<T>> {
+    type Error = Error;
+
+    fn try_from(val: &Image<T>) -> error::Result<Self> {
+        Self::try_from(val.clone())
+    }
+}
+
+impl<T: Channel> TryFrom<Image<T>> for ImageBuffer<T::RgbaPixel, Vec<T>> {
+    type Error = Error;
+
+    /// Moves the pixel data into the buffer.
+    fn try_from(val: Image<T>) -> error::Result<Self> {
+        let (width, height) = (val.width, val.height);
+        ImageBuffer::from_raw(width, height, val.pixel_data)
+            .ok_or(Error::ImageAllocation { width, height })
+    }
+}
+
+impl<T: Channel> TryFrom<Image<T>> for DynamicImage {
+    type Error = Error;
+
+    /// Moves the pixel data into the image.
+    fn try_from(val: Image<T>) -> error::Result<Self> {
+        ImageBuffer::try_from(val).map(T::into_dynamic_image)
+    }
+}
+
+impl<T: Channel> From<ImageBuffer<T::RgbaPixel, Vec<T>>> for Image<T> {
+    fn from(val: ImageBuffer<T::RgbaPixel, Vec<T>>) -> Self {
+        Self {
+            width: val.width(),
+            height: val.height(),
+            pixel_data: val.into_raw(),
+            channels: 4,
+        }
+    }
+}
+
+/// Takes over the pixel data if the image already has RGBA channels of type
+/// `T`, otherwise converts it.
+impl<T: Channel> From<DynamicImage> for Image<T> {
+    fn from(val: DynamicImage) -> Self {
+        Self::from(T::from_dynamic_image(val))
+    }
+}
+
+pub fn save_to_file<T: Channel, P: AsRef<std::path::Path>>(
+    image: &Image<T>,
+    path: P,
+    format: image::ImageFormat,
+) -> Result<(), ImageError> {
+    image::save_buffer_with_format(
+        path,
+        image.as_bytes(),
+        image.width,
+        image.height,
+        T::COLOR_TYPE,
+        format,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_image() -> Image {
+        let mut image = Image::new(4, 2);
+        for y in 0..2 {
+            for x in 0..4 {
+                image.set_pixel(x, y, Rgba::new(x as u8 * 10, y as u8, 0, 255));
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn get_pixel_should_return_set_pixel() {
+        let image = gradient_image();
+
+        assert_eq!(image.get_pixel(3, 1), Some(Rgba::new(30, 1, 0, 255)));
+        assert_eq!(image.get_pixel(4, 0), None);
+    }
+
+    #[test]
+    fn view_should_borrow_rows_of_region() {
+        let image = gradient_image();
+
+        let view = image.view(RenderRegion::new(1, 1, 2, 1)).unwrap();
+
+        assert_eq!(view.get_pixel(0, 0), Some(Rgba::new(10, 1, 0, 255)));
+        assert_eq!(
+            view.rows().collect::<Vec<_>>(),
+            vec![&[10, 1, 0, 255, 20, 1, 0, 255][..]]
+        );
+        assert_eq!(view.to_image().get_pixel(1, 0), image.get_pixel(2, 1));
+        assert!(image.view(RenderRegion::new(3, 0, 2, 1)).is_none());
+    }
+
+    #[test]
+    fn rows_should_be_empty_for_zero_width() {
+        let mut image = Image::<u8>::new(0, 3);
+
+        assert_eq!(image.rows().count(), 0);
+        assert_eq!(image.rows_mut().count(), 0);
+        assert_eq!(image.downsample(2).rows().count(), 0);
+        assert!(image.view(RenderRegion::new(0, 0, 1, 1)).is_none());
+    }
+
+    #[test]
+    fn downsample_should_average_blocks() {
+        let image = gradient_image();
+
+        let downsampled = image.downsample(2);
+
+        assert_eq!(downsampled.width(), 2);
+        assert_eq!(downsampled.height(), 1);
+        assert_eq!(downsampled.get_pixel(1, 0), Some(Rgba::new(25, 1, 0, 255)));
+    }
+
+    #[test]
+    fn resize_should_scale_image() {
+        let resized = gradient_image().resize(8, 4, FilterType::Nearest);
+
+        assert_eq!(resized.width(), 8);
+        assert_eq!(resized.get_pixel(7, 3), Some(Rgba::new(30, 1, 0, 255)));
+    }
+
+    #[test]
+    fn convert_should_scale_to_channel_range() {
+        let image = gradient_image().convert::<u16>();
+
+        assert_eq!(image.get_pixel(1, 0), Some(Rgba::new(2570, 0, 0, 65535)));
+        assert_eq!(image.convert::<f32>().get_pixel(0, 0).unwrap().a(), 1.0);
+    }
+
+    #[test]
+    fn dynamic_image_conversion_should_move_pixel_data() {
+        let image = gradient_image();
+        let pointer = image.as_slice().as_ptr();
+
+        let dynamic_image = DynamicImage::try_from(image).unwrap();
+        let image = Image::<u8>::from(dynamic_image);
+
+        assert_eq!(image.as_slice().as_ptr(), pointer);
+        assert_eq!(image.get_pixel(3, 1), Some(Rgba::new(30, 1, 0, 255)));
+    }
+}
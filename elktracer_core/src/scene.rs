@@ -0,0 +1,75 @@
+use crate::{
+    color::Color,
+    math::{interval::Interval, ray::Ray},
+    ray_hit::{RayHitDetails, RayHitTest},
+};
+
+/// Number of rays traced through the scene and the tests against its
+/// objects they needed, see `RenderStats::object_tests`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RayCounters {
+    pub rays: u64,
+    pub object_tests: u64,
+}
+
+pub struct Scene {
+    background_gradient_start: Color,
+    background_gradient_end: Color,
+    objects: Vec<Box<dyn RayHitTest>>,
+    counters: RayCounters,
+}
+
+impl Scene {
+    pub fn new(objects: Vec<Box<dyn RayHitTest>>) -> Self {
+        Self {
+            background_gradient_start: Color::new(0.3, 0.6, 0.9),
+            background_gradient_end: Color::new(1.0, 1.0, 1.0),
+            objects,
+            counters: RayCounters::default(),
+        }
+    }
+
+    pub fn set_objects(&mut self, objects: Vec<Box<dyn RayHitTest>>) {
+        self.objects = objects;
+        self.counters = RayCounters::default();
+    }
+
+    pub fn counters(&self) -> RayCounters {
+        self.counters
+    }
+
+    pub fn does_hit(
+        &mut self,
+        ray: &Ray,
+        ray_t: &Interval,
+    ) -> Option<RayHitDetails> {
+        let mut hit_result: Option<RayHitDetails> = None;
+        let mut closest = ray_t.max();
+
+        self.counters.rays += 1;
+        self.counters.object_tests += self.objects.len() as u64;
+
+        for object in self.objects.iter() {
+            if let Some(hit) =
+                object.does_hit(ray, &Interval::new(ray_t.min(), closest))
+            {
+                closest = hit.t();
+                hit_result = Some(hit);
+            }
+        }
+
+        hit_result
+    }
+
+    pub fn background_color(&self, ray: &Ray) -> Color {
+        let a: f64 = (ray.direction().unit().y() + 1.0) * 0.5;
+        self.background_gradient_end * (1.0 - a)
+            + self.background_gradient_start * a
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
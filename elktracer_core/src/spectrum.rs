@@ -0,0 +1,150 @@
+use std::sync::OnceLock;
+
+use crate::{color::Color, random};
+
+pub const WAVELENGTH_MIN: f64 = 380.0;
+pub const WAVELENGTH_MAX: f64 = 780.0;
+
+/// Centers of the smooth basis spectra used to uplift RGB values, in nm.
+pub const UPLIFT_CENTERS: [f64; 3] = [610.0, 545.0, 450.0];
+const UPLIFT_WIDTH: f64 = 40.0;
+
+const XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [3.2406, -1.5372, -0.4986],
+    [-0.9689, 1.8758, 0.0415],
+    [0.0557, -0.2040, 1.0570],
+];
+
+/// Uniformly samples a visible wavelength in nm.
+pub fn sample_wavelength() -> f64 {
+    WAVELENGTH_MIN
+        + random::random_f64_0_1() * (WAVELENGTH_MAX - WAVELENGTH_MIN)
+}
+
+/// Evaluates the spectrum of an RGB reflectance or emission at the given
+/// wavelength. The basis spectra sum to one everywhere, so white becomes a
+/// flat spectrum of one.
+pub fn rgb_to_spectral(color: Color, wavelength: f64) -> f64 {
+    let weights = UPLIFT_CENTERS.map(|center| {
+        let x = (wavelength - center) / UPLIFT_WIDTH;
+        f64::exp(-0.5 * x * x)
+    });
+    let sum: f64 = weights.iter().sum();
+
+    (color.r() * weights[0] + color.g() * weights[1] + color.b() * weights[2])
+        / sum
+}
+
+/// Collapses a color to its spectral value if a wavelength is being traced
+/// and leaves it untouched in RGB mode.
+pub fn for_wavelength(color: Color, wavelength: Option<f64>) -> Color {
+    match wavelength {
+        Some(wavelength) => {
+            let value = rgb_to_spectral(color, wavelength);
+            Color::new(value, value, value)
+        }
+        None => color,
+    }
+}
+
+/// Turns the radiance carried by a single uniformly sampled wavelength into
+/// a linear sRGB estimate. Averaging many samples converges to the color of
+/// the full spectrum, white balanced so a flat spectrum yields white.
+pub fn wavelength_to_rgb(radiance: f64, wavelength: f64) -> Color {
+    let white = white_balance();
+    let [x, y, z] = cie_xyz(wavelength);
+    let scale = radiance * (WAVELENGTH_MAX - WAVELENGTH_MIN) / white.y_integral;
+    let [r, g, b] = xyz_to_linear_srgb([x * scale, y * scale, z * scale]);
+
+    Color::new(r / white.rgb[0], g / white.rgb[1], b / white.rgb[2])
+}
+
+struct WhiteBalance {
+    y_integral: f64,
+    rgb: [f64; 3],
+}
+
+fn white_balance() -> &'static WhiteBalance {
+    static WHITE_BALANCE: OnceLock<WhiteBalance> = OnceLock::new();
+    WHITE_BALANCE.get_or_init(|| {
+        let mut xyz = [0.0; 3];
+        let mut wavelength = WAVELENGTH_MIN;
+        while wavelength < WAVELENGTH_MAX {
+            let value = cie_xyz(wavelength + 0.5);
+            xyz[0] += value[0];
+            xyz[1] += value[1];
+            xyz[2] += value[2];
+            wavelength += 1.0;
+        }
+        let y_integral = xyz[1];
+
+        WhiteBalance {
+            y_integral,
+            rgb: xyz_to_linear_srgb(xyz.map(|value| value / y_integral)),
+        }
+    })
+}
+
+fn xyz_to_linear_srgb(xyz: [f64; 3]) -> [f64; 3] {
+    XYZ_TO_LINEAR_SRGB
+        .map(|row| row[0] * xyz[0] + row[1] * xyz[1] + row[2] * xyz[2])
+}
+
+/// CIE 1931 color matching functions using the multi-lobe fit by Wyman,
+/// Sloan and Shirley (2013).
+fn cie_xyz(wavelength: f64) -> [f64; 3] {
+    let lobe = |mu: f64, sigma_low: f64, sigma_high: f64| {
+        let sigma = if wavelength < mu {
+            sigma_low
+        } else {
+            sigma_high
+        };
+        let t = (wavelength - mu) / sigma;
+        f64::exp(-0.5 * t * t)
+    };
+
+    [
+        1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+            - 0.065 * lobe(501.1, 20.4, 26.2),
+        0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1),
+        1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    #[test]
+    fn rgb_to_spectral_should_keep_white_flat() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        for wavelength in [380.0, 450.0, 555.0, 700.0, 780.0] {
+            assert_approx_eq!(rgb_to_spectral(white, wavelength), 1.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn wavelength_to_rgb_should_integrate_flat_spectrum_to_white() {
+        let steps = 4000;
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+        for step in 0..steps {
+            let wavelength = WAVELENGTH_MIN
+                + (step as f64 + 0.5) / steps as f64
+                    * (WAVELENGTH_MAX - WAVELENGTH_MIN);
+            sum += wavelength_to_rgb(1.0, wavelength);
+        }
+        let average = sum * (1.0 / steps as f64);
+
+        let epsilon = 1e-3;
+        assert_approx_eq!(average.r(), 1.0, epsilon);
+        assert_approx_eq!(average.g(), 1.0, epsilon);
+        assert_approx_eq!(average.b(), 1.0, epsilon);
+    }
+
+    #[test]
+    fn for_wavelength_should_not_change_rgb_colors() {
+        let color = Color::new(0.2, 0.4, 0.6);
+        assert_eq!(for_wavelength(color, None), color);
+    }
+}
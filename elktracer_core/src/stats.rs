@@ -0,0 +1,123 @@
+use std::{fmt, time::Duration};
+
+/// Measurements of a single `render_image` call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderStats {
+    pub wall_time: Duration,
+    /// Processor time of the whole process, if the platform reports it.
+    pub cpu_time: Option<Duration>,
+    /// Rays generated by the camera.
+    pub primary_rays: u64,
+    /// Rays traced by the integrator after the primary ray, including
+    /// shadow and occlusion rays.
+    pub secondary_rays: u64,
+    /// Intersection tests of all rays with the objects of the scene. Groups,
+    /// instances and CSG objects count as one test, however many objects
+    /// they contain.
+    pub object_tests: u64,
+    /// Number of camera samples indexed by the count of rays traced for
+    /// them. Samples blocked by the camera have a path length of zero.
+    pub path_length_histogram: Vec<u64>,
+    /// Samples with a NaN or infinite color, which are discarded.
+    pub invalid_samples: u64,
+}
+
+impl RenderStats {
+    pub fn rays(&self) -> u64 {
+        self.primary_rays + self.secondary_rays
+    }
+
+    pub fn rays_per_second(&self) -> f64 {
+        let seconds = self.wall_time.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.rays() as f64 / seconds
+    }
+
+    pub fn object_tests_per_ray(&self) -> f64 {
+        if self.rays() == 0 {
+            return 0.0;
+        }
+        self.object_tests as f64 / self.rays() as f64
+    }
+
+    pub(crate) fn record_path_length(&mut self, rays: u64) {
+        let index = rays as usize;
+        if self.path_length_histogram.len() <= index {
+            self.path_length_histogram.resize(index + 1, 0);
+        }
+        self.path_length_histogram[index] += 1;
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Wall time:            {:.3} s",
+            self.wall_time.as_secs_f64()
+        )?;
+        match self.cpu_time {
+            Some(cpu_time) => writeln!(
+                f,
+                "CPU time:             {:.3} s",
+                cpu_time.as_secs_f64()
+            )?,
+            None => writeln!(f, "CPU time:             n/a")?,
+        }
+        writeln!(f, "Primary rays:         {}", self.primary_rays)?;
+        writeln!(f, "Secondary rays:       {}", self.secondary_rays)?;
+        writeln!(f, "Rays per second:      {:.0}", self.rays_per_second())?;
+        writeln!(
+            f,
+            "Object tests per ray: {:.2}",
+            self.object_tests_per_ray()
+        )?;
+        writeln!(f, "Invalid samples:      {}", self.invalid_samples)?;
+        write!(f, "Path lengths:")?;
+        for (length, count) in self.path_length_histogram.iter().enumerate() {
+            if *count > 0 {
+                write!(f, " {}: {}", length, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Processor time consumed by all threads of the process so far.
+#[cfg(unix)]
+pub(crate) fn process_cpu_time() -> Option<Duration> {
+    cpu_time::ProcessTime::try_now()
+        .ok()
+        .map(|time| time.as_duration())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_path_length_should_grow_histogram() {
+        let mut stats = RenderStats::default();
+
+        stats.record_path_length(3);
+        stats.record_path_length(1);
+        stats.record_path_length(3);
+
+        assert_eq!(stats.path_length_histogram, vec![0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn rates_should_be_zero_without_rays() {
+        let stats = RenderStats::default();
+
+        assert_eq!(stats.rays_per_second(), 0.0);
+        assert_eq!(stats.object_tests_per_ray(), 0.0);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::math::vector3::Vec3f;
+
+/// Scalar texture evaluated at a point in world space.
+pub trait Texture: Send + Sync {
+    fn value(&self, point: Vec3f) -> f64;
+}
+
+pub struct ConstantTexture {
+    value: f64,
+}
+
+impl ConstantTexture {
+    pub fn new(value: f64) -> Self {
+        Self { value }
+    }
+}
+
+impl Texture for ConstantTexture {
+    fn value(&self, _point: Vec3f) -> f64 {
+        self.value
+    }
+}
+
+/// 3D checker pattern alternating between `even` and `odd` every `scale`
+/// units along each axis.
+pub struct CheckerTexture {
+    inverse_scale: f64,
+    even: f64,
+    odd: f64,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: f64, odd: f64) -> Self {
+        Self {
+            inverse_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, point: Vec3f) -> f64 {
+        let sum = f64::floor(point.x() * self.inverse_scale) as i64
+            + f64::floor(point.y() * self.inverse_scale) as i64
+            + f64::floor(point.z() * self.inverse_scale) as i64;
+
+        if sum % 2 == 0 { self.even } else { self.odd }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checker_should_alternate() {
+        let texture = CheckerTexture::new(1.0, 0.0, 1.0);
+
+        assert_eq!(texture.value(Vec3f::new(0.5, 0.5, 0.5)), 0.0);
+        assert_eq!(texture.value(Vec3f::new(1.5, 0.5, 0.5)), 1.0);
+        assert_eq!(texture.value(Vec3f::new(-0.5, 0.5, 0.5)), 1.0);
+        assert_eq!(texture.value(Vec3f::new(1.5, 1.5, 0.5)), 0.0);
+    }
+}
@@ -1,6 +1,35 @@
+/// Allocates a vector of `len` copies of `value`, or returns `None` if the
+/// memory is not available.
+pub fn try_filled_vec<T: Clone>(len: usize, value: T) -> Option<Vec<T>> {
+    let mut vec = Vec::new();
+    vec.try_reserve_exact(len).ok()?;
+    vec.resize(len, value);
+    Some(vec)
+}
+
+/// Reads exactly `N` bytes, mapping read errors with `error`.
+pub fn read_array<const N: usize, E>(
+    reader: &mut impl std::io::Read,
+    error: impl Fn(std::io::Error) -> E,
+) -> Result<[u8; N], E> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes).map_err(error)?;
+    Ok(bytes)
+}
+
 #[cfg(test)]
 #[macro_use]
-mod test_utils {
+pub(crate) mod test_utils {
+    use std::sync::Arc;
+
+    use crate::{
+        color::Color,
+        material::{Material, lambert::LambertMaterial},
+        math::vector3::Vec3f,
+        object::sphere::Sphere,
+        ray_hit::RayHitTest,
+    };
+
     /// Asserts that two floating-point numbers are approximately equal within a given epsilon.
     #[macro_export]
     macro_rules! assert_approx_eq {
@@ -13,4 +42,14 @@ mod test_utils {
             }
         };
     }
+
+    /// Diffuse gray material of the objects of tests.
+    pub fn gray_material() -> Arc<dyn Material> {
+        Arc::new(LambertMaterial::new(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    /// Gray sphere as an object of a scene, group or CSG.
+    pub fn sphere(center: Vec3f, radius: f64) -> Box<dyn RayHitTest> {
+        Box::new(Sphere::new(center, radius, gray_material()))
+    }
 }
@@ -6,7 +6,7 @@ use elktracer_json::model::SceneModel;
 use crate::ui::{
     camera::ui_for_camera_options, material::ui_for_materials,
     preview::ui_for_preview, rendering::ui_for_rendering_options,
-    scene_objects::ui_for_scene_objects,
+    scene_objects::ui_for_scene_objects, statistics::ui_for_render_stats,
 };
 
 #[derive(Debug)]
@@ -17,6 +17,7 @@ pub enum GuiWindow {
     Camera,
     Rendering,
     Preview,
+    Statistics,
     Debug,
 }
 
@@ -29,6 +30,8 @@ pub struct Application<'a> {
     // pub scene_objects: &'a mut Vec<ObjectModel>,
     pub render_options: &'a mut elktracer_core::RenderOptions,
     pub preview_render_options: &'a mut elktracer_core::RenderOptions,
+    /// Index of the scene object picked on a rendered image.
+    pub selected_object: &'a mut Option<usize>,
 }
 
 impl egui_dock::TabViewer for Application<'_> {
@@ -42,7 +45,9 @@ impl egui_dock::TabViewer for Application<'_> {
             GuiWindow::SceneObjects => ui_for_scene_objects(
                 ui,
                 &self.scene_model.materials,
+                &self.scene_model.groups,
                 &mut self.scene_model.objects,
+                self.selected_object,
             ),
             GuiWindow::Materials => {
                 ui_for_materials(ui, &mut self.scene_model.materials)
@@ -55,13 +60,16 @@ impl egui_dock::TabViewer for Application<'_> {
                 self.world,
                 self.scene_model,
                 self.render_options,
+                self.selected_object,
             ),
+            GuiWindow::Statistics => ui_for_render_stats(ui, self.world),
             GuiWindow::Debug => ui_for_world(self.world, ui),
             GuiWindow::Preview => ui_for_preview(
                 ui,
                 self.world,
                 self.scene_model,
                 self.preview_render_options,
+                self.selected_object,
             ),
         }
     }
@@ -131,6 +131,7 @@ struct UiState {
     scene_model: SceneModel,
     render_options: elktracer_core::RenderOptions,
     preview_render_options: elktracer_core::RenderOptions,
+    selected_object: Option<usize>,
 }
 
 impl UiState {
@@ -152,6 +153,7 @@ impl UiState {
             0.75,
             vec![
                 application::GuiWindow::Materials,
+                application::GuiWindow::Statistics,
                 application::GuiWindow::Debug,
             ],
         );
@@ -254,6 +256,7 @@ impl UiState {
                 10,
                 10,
             ),
+            selected_object: None,
         }
     }
 
@@ -287,6 +290,7 @@ impl UiState {
                     scene_model: &mut self.scene_model,
                     render_options: &mut self.render_options,
                     preview_render_options: &mut self.preview_render_options,
+                    selected_object: &mut self.selected_object,
                 };
                 DockArea::new(&mut self.state)
                     .style(style)
@@ -299,7 +303,13 @@ impl UiState {
             .add_filter("Scene (json)", &["json"])
             .pick_file()
         {
-            self.scene_model = load_scene_model(&file);
+            match load_scene_model(&file) {
+                Ok(scene_model) => {
+                    self.scene_model = scene_model;
+                    self.selected_object = None;
+                }
+                Err(error) => bevy::log::error!("{}", error),
+            }
         }
     }
 
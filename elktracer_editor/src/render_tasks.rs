@@ -5,13 +5,17 @@ use bevy::{
     tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
 };
 use bevy_egui::{EguiContexts, egui::TextureId};
-use elktracer_core::image_rs::RgbaImage;
+use elktracer_core::image_rs::DynamicImage;
 use elktracer_json::model::SceneModel;
 
 #[derive(Resource, Default)]
 pub struct ElktracerRenderSystem {
-    pub tasks: HashMap<String, Task<elktracer_core::Image>>,
+    pub tasks: HashMap<
+        String,
+        Task<Result<elktracer_core::RenderedImage, elktracer_core::Error>>,
+    >,
     pub texture_ids: HashMap<String, TextureId>,
+    pub stats: HashMap<String, elktracer_core::RenderStats>,
 }
 
 pub fn spawn_render_task(
@@ -20,7 +24,7 @@ pub fn spawn_render_task(
     // materials: Vec<MaterialModel>,
     // scene_objects: Vec<ObjectModel>,
     render_options: elktracer_core::RenderOptions,
-) -> Task<elktracer_core::Image> {
+) -> Task<Result<elktracer_core::RenderedImage, elktracer_core::Error>> {
     let task_pool = AsyncComputeTaskPool::get();
     task_pool.spawn(async move {
         let mut raytracer = elktracer_core::Raytracer::new();
@@ -70,10 +74,11 @@ pub fn spawn_render_task(
         //     })
         //     .collect();
 
-        let camera = elktracer_core::Camera::from(scene_model.camera.clone());
-        let objects = elktracer_json::get_scene_objects(&scene_model);
+        let camera =
+            elktracer_core::Camera::try_from(scene_model.camera.clone())?;
+        let objects = elktracer_json::get_scene_objects(&scene_model)?;
 
-        raytracer.render_image(&camera, objects, &render_options)
+        raytracer.try_render_image(&camera, objects, &render_options)
     })
 }
 
@@ -83,22 +88,32 @@ pub fn handle_elktracer_render_tasks(
     mut contexts: EguiContexts,
 ) {
     let mut finished_images: HashMap<String, Handle<Image>> = HashMap::new();
+    let mut finished_stats: HashMap<String, elktracer_core::RenderStats> =
+        HashMap::new();
 
     render_system.tasks.retain(|task_id, task| {
         let status = block_on(future::poll_once(task));
         let should_retain_task = status.is_none();
 
-        if let Some(image) = status {
-            info!("Finished {}", task_id);
-
-            let image_handle = images.add(Image::from_dynamic(
-                elktracer_core::image_rs::DynamicImage::from(RgbaImage::from(
-                    image,
-                )),
-                true,
-                RenderAssetUsages::all(),
-            ));
-            finished_images.insert(task_id.clone(), image_handle);
+        match status {
+            Some(Ok(result)) => {
+                info!("Finished {}", task_id);
+
+                match DynamicImage::try_from(result.image) {
+                    Ok(image) => {
+                        let image_handle = images.add(Image::from_dynamic(
+                            image,
+                            true,
+                            RenderAssetUsages::all(),
+                        ));
+                        finished_images.insert(task_id.clone(), image_handle);
+                    }
+                    Err(err) => error!("Unable to show {}: {}", task_id, err),
+                }
+                finished_stats.insert(task_id.clone(), result.stats);
+            }
+            Some(Err(err)) => error!("Failed {}: {}", task_id, err),
+            None => {}
         }
 
         should_retain_task
@@ -109,6 +124,7 @@ pub fn handle_elktracer_render_tasks(
             .texture_ids
             .insert(key, contexts.add_image(handle));
     }
+    render_system.stats.extend(finished_stats);
 }
 
 // fn poll_task(
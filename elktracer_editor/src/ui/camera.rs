@@ -1,7 +1,10 @@
 use bevy_egui::egui::{self, Grid};
-use elktracer_json::model::CameraModel;
+use elktracer_core::{Camera, PhysicalCamera, Projection};
+use elktracer_json::model::{
+    ApertureModel, CameraModel, PhysicalCameraModel, ProjectionModel,
+};
 
-use super::{ui_for_number, ui_for_vector};
+use super::{ui_for_number, ui_for_string, ui_for_vector};
 
 pub fn ui_for_camera_options(ui: &mut egui::Ui, camera: &mut CameraModel) {
     egui::Frame::new().outer_margin(6.0).show(ui, |ui| {
@@ -38,15 +41,197 @@ fn ui_for_vector_attributes(ui: &mut egui::Ui, camera: &mut CameraModel) {
 }
 
 fn ui_for_number_attributes(ui: &mut egui::Ui, camera: &mut CameraModel) {
-    ui.label("Vertical field of view °");
-    ui_for_number(ui, &mut camera.fov_vertical_degrees, 0.1, Some(0.0..=360.0));
+    ui.label("Projection");
+    ui_for_projection(ui, &mut camera.projection);
     ui.end_row();
 
-    ui.label("Defocus angle °");
-    ui_for_number(ui, &mut camera.defocus_angle, 0.1, Some(0.0..=360.0));
+    ui.label("Physical camera");
+    let mut is_physical = camera.physical.is_some();
+    if ui.checkbox(&mut is_physical, "").changed() {
+        camera.physical = is_physical.then(PhysicalCameraModel::default);
+    }
     ui.end_row();
 
+    if camera.physical.is_none() {
+        ui.label("Vertical field of view °");
+        let fov_vertical_degrees = camera
+            .fov_vertical_degrees
+            .get_or_insert(Camera::default().fov_vertical_degrees);
+        ui_for_number(ui, fov_vertical_degrees, 0.1, Some(0.1..=179.9));
+        ui.end_row();
+
+        ui.label("Defocus angle °");
+        ui_for_number(ui, &mut camera.defocus_angle, 0.1, Some(0.0..=360.0));
+        ui.end_row();
+    }
+
     ui.label("Focus distance");
     ui_for_number(ui, &mut camera.focus_distance, 0.1, Some(0.0..=f64::MAX));
     ui.end_row();
+
+    if let Some(physical) = &mut camera.physical {
+        ui_for_physical_attributes(ui, physical, camera.focus_distance);
+    }
+
+    ui_for_lens_attributes(ui, camera);
+
+    ui.label("Shutter open s");
+    ui_for_number(ui, &mut camera.shutter_open, 0.001, None);
+    ui.end_row();
+
+    if camera.physical.is_none() {
+        ui.label("Shutter close s");
+        ui_for_number(ui, &mut camera.shutter_close, 0.001, None);
+        ui.end_row();
+    }
+}
+
+fn ui_for_lens_attributes(ui: &mut egui::Ui, camera: &mut CameraModel) {
+    ui.label("Aperture");
+    ui_for_aperture(ui, &mut camera.aperture);
+    ui.end_row();
+
+    match &mut camera.aperture {
+        ApertureModel::Disk => {}
+        ApertureModel::Polygon {
+            blades,
+            rotation_degrees,
+        } => {
+            ui.label("Blades");
+            ui_for_number(ui, blades, 0.1, Some(3..=16));
+            ui.end_row();
+
+            ui.label("Rotation °");
+            ui_for_number(ui, rotation_degrees, 0.5, Some(0.0..=360.0));
+            ui.end_row();
+        }
+        ApertureModel::Mask { file, .. } => {
+            ui.label("Mask image");
+            ui.horizontal(|ui| {
+                ui_for_string(ui, file, "Image file");
+                if ui.button("…").clicked()
+                    && let Some(path) = pick_aperture_mask_file()
+                {
+                    *file = path;
+                }
+            });
+            ui.end_row();
+        }
+    }
+
+    ui.label("Cat's-eye vignetting");
+    ui_for_number(ui, &mut camera.cat_eye, 0.01, Some(0.0..=2.0));
+    ui.end_row();
+
+    ui.label("Chromatic aberration");
+    ui_for_number(
+        ui,
+        &mut camera.chromatic_aberration,
+        0.001,
+        Some(-0.1..=0.1),
+    );
+    ui.end_row();
+}
+
+fn ui_for_aperture(ui: &mut egui::Ui, aperture: &mut ApertureModel) {
+    let selected = match aperture {
+        ApertureModel::Disk => "Disk",
+        ApertureModel::Polygon { .. } => "Polygon",
+        ApertureModel::Mask { .. } => "Mask",
+    };
+
+    egui::ComboBox::new("camera_aperture_combo_box", "")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(selected == "Disk", "Disk").clicked() {
+                *aperture = ApertureModel::Disk;
+            }
+            if ui
+                .selectable_label(selected == "Polygon", "Polygon")
+                .clicked()
+                && selected != "Polygon"
+            {
+                *aperture = ApertureModel::Polygon {
+                    blades: 6,
+                    rotation_degrees: 0.0,
+                };
+            }
+            if ui.selectable_label(selected == "Mask", "Mask").clicked()
+                && selected != "Mask"
+                && let Some(file) = pick_aperture_mask_file()
+            {
+                *aperture = ApertureModel::Mask {
+                    file,
+                    directory: None,
+                };
+            }
+        });
+}
+
+fn ui_for_physical_attributes(
+    ui: &mut egui::Ui,
+    physical: &mut PhysicalCameraModel,
+    focus_distance: f64,
+) {
+    ui.label("Focal length mm");
+    ui_for_number(ui, &mut physical.focal_length_mm, 0.5, Some(1.0..=2000.0));
+    ui.end_row();
+
+    ui.label("Sensor width mm");
+    ui_for_number(ui, &mut physical.sensor_width_mm, 0.1, Some(1.0..=100.0));
+    ui.end_row();
+
+    ui.label("Sensor height mm");
+    ui_for_number(ui, &mut physical.sensor_height_mm, 0.1, Some(1.0..=100.0));
+    ui.end_row();
+
+    ui.label("f-number");
+    ui_for_number(ui, &mut physical.f_number, 0.1, Some(0.5..=64.0));
+    ui.end_row();
+
+    ui.label("Shutter s");
+    ui_for_number(ui, &mut physical.shutter_seconds, 0.001, Some(0.0..=60.0));
+    ui.end_row();
+
+    ui.label("ISO");
+    ui_for_number(ui, &mut physical.iso, 10.0, Some(25.0..=102400.0));
+    ui.end_row();
+
+    let derived = PhysicalCamera::from(*physical);
+
+    ui.label("Vertical field of view °");
+    ui.label(format!("{:.2}", derived.fov_vertical_degrees()));
+    ui.end_row();
+
+    ui.label("Defocus angle °");
+    ui.label(format!("{:.3}", derived.defocus_angle(focus_distance)));
+    ui.end_row();
+
+    ui.label("Exposure");
+    ui.label(format!("{:.3}", derived.exposure()));
+    ui.end_row();
+}
+
+fn ui_for_projection(ui: &mut egui::Ui, projection: &mut ProjectionModel) {
+    let selected = Projection::from(*projection);
+
+    egui::ComboBox::new("camera_projection_combo_box", "")
+        .selected_text(selected.to_string())
+        .show_ui(ui, |ui| {
+            for option in Projection::ALL {
+                if ui
+                    .selectable_label(selected == option, option.to_string())
+                    .clicked()
+                {
+                    *projection = ProjectionModel::from(option);
+                }
+            }
+        });
+}
+
+fn pick_aperture_mask_file() -> Option<String> {
+    rfd::FileDialog::new()
+        .add_filter("Image", &["png", "jpg", "jpeg"])
+        .pick_file()
+        .map(|path| path.display().to_string())
 }
@@ -1,11 +1,20 @@
 use bevy_egui::egui::{self, Button, Color32, Grid, Stroke};
-use elktracer_json::model::{MaterialModel, MaterialType};
+use elktracer_core::{ConductorPreset, Dispersion};
+use elktracer_json::model::{
+    DispersionModel, MaterialModel, MaterialType, ProceduralTextureModel,
+    TextureModel,
+};
 
-use super::{ui_for_number, ui_for_string};
+use super::{ui_for_number, ui_for_string, ui_for_vector};
 
 pub fn ui_for_materials(ui: &mut egui::Ui, materials: &mut Vec<MaterialModel>) {
     ui.add_space(4.0);
 
+    let default_material_id = materials
+        .first()
+        .map(|material| material.id.clone())
+        .unwrap_or_default();
+
     ui.horizontal(|ui| {
         ui.add_space(6.0);
 
@@ -31,16 +40,70 @@ pub fn ui_for_materials(ui: &mut egui::Ui, materials: &mut Vec<MaterialModel>) {
                 id: "New Transparent Material".to_string(),
                 material_type: MaterialType::Transparent {
                     refraction_index: 1.0,
+                    absorption_color: [1.0, 1.0, 1.0],
+                    absorption_density: 0.0,
+                    roughness: 0.0,
+                    dispersion: None,
+                },
+            });
+        }
+        if ui.button("Add Conductor").clicked() {
+            let preset = ConductorPreset::Gold;
+            materials.push(MaterialModel {
+                id: "New Conductor Material".to_string(),
+                material_type: MaterialType::Conductor {
+                    eta: preset.eta(),
+                    k: preset.k(),
+                    roughness: 0.2,
+                },
+            });
+        }
+        if ui.button("Add Principled").clicked() {
+            materials.push(MaterialModel {
+                id: "New Principled Material".to_string(),
+                material_type: MaterialType::Principled {
+                    base_color: [0.8, 0.8, 0.8],
+                    metallic: 0.0,
+                    roughness: 0.5,
+                    specular: 0.5,
+                    clearcoat: 0.0,
+                    sheen: 0.0,
+                    transmission: 0.0,
+                },
+            });
+        }
+        if ui.button("Add Mix").clicked() {
+            materials.push(MaterialModel {
+                id: "New Mix Material".to_string(),
+                material_type: MaterialType::Mix {
+                    first: default_material_id.clone(),
+                    second: default_material_id.clone(),
+                    weight: TextureModel::Constant(0.5),
+                },
+            });
+        }
+        if ui.button("Add Coated").clicked() {
+            materials.push(MaterialModel {
+                id: "New Coated Material".to_string(),
+                material_type: MaterialType::Coated {
+                    base: default_material_id.clone(),
+                    refraction_index: 1.5,
+                    roughness: 0.0,
+                    tint: [1.0, 1.0, 1.0],
                 },
             });
         }
     });
 
+    let material_ids: Vec<String> = materials
+        .iter()
+        .map(|material| material.id.clone())
+        .collect();
     let mut to_be_removed: Option<usize> = None;
 
     for (index, material) in materials.iter_mut().enumerate() {
         let mut remove = false;
-        ui_for_list_item(ui, index, material, &mut remove);
+        ui_for_list_item(ui, index, material, &material_ids, &mut remove);
         if remove {
             to_be_removed = Some(index);
         }
@@ -55,6 +118,7 @@ fn ui_for_list_item(
     ui: &mut egui::Ui,
     index: usize,
     material: &mut MaterialModel,
+    material_ids: &[String],
     should_be_removed: &mut bool,
 ) {
     egui::Frame::new()
@@ -80,6 +144,18 @@ fn ui_for_list_item(
                             MaterialType::Transparent { .. } => {
                                 ui.label("Transparent");
                             }
+                            MaterialType::Conductor { .. } => {
+                                ui.label("Conductor");
+                            }
+                            MaterialType::Principled { .. } => {
+                                ui.label("Principled");
+                            }
+                            MaterialType::Mix { .. } => {
+                                ui.label("Mix");
+                            }
+                            MaterialType::Coated { .. } => {
+                                ui.label("Coated");
+                            }
                         }
 
                         ui_for_string(ui, &mut material.id, "Material ID");
@@ -96,7 +172,12 @@ fn ui_for_list_item(
                             .num_columns(2) // Define two columns: one for labels, one for widgets
                             .spacing([10.0, 5.0]) // Horizontal and vertical spacing between items
                             .show(ui, |ui| {
-                                ui_for_material_type(ui, material);
+                                ui_for_material_type(
+                                    ui,
+                                    index,
+                                    material,
+                                    material_ids,
+                                );
                             });
                     });
                 },
@@ -104,7 +185,12 @@ fn ui_for_list_item(
         });
 }
 
-fn ui_for_material_type(ui: &mut egui::Ui, material: &mut MaterialModel) {
+fn ui_for_material_type(
+    ui: &mut egui::Ui,
+    index: usize,
+    material: &mut MaterialModel,
+    material_ids: &[String],
+) {
     match &mut material.material_type {
         MaterialType::Lambert { albedo } => {
             ui.label("Albedo:");
@@ -120,10 +206,278 @@ fn ui_for_material_type(ui: &mut egui::Ui, material: &mut MaterialModel) {
             ui_for_number(ui, fuzziness, 0.1, None);
             ui.end_row();
         }
-        MaterialType::Transparent { refraction_index } => {
+        MaterialType::Transparent {
+            refraction_index,
+            absorption_color,
+            absorption_density,
+            roughness,
+            dispersion,
+        } => {
             ui.label("Refraction index:");
             ui_for_number(ui, refraction_index, 0.1, None);
             ui.end_row();
+
+            ui.label("Absorption color:");
+            ui.color_edit_button_rgb(absorption_color);
+            ui.end_row();
+
+            ui.label("Absorption density:");
+            ui_for_number(ui, absorption_density, 0.1, Some(0.0..=f64::MAX));
+            ui.end_row();
+
+            ui.label("Roughness:");
+            ui_for_number(ui, roughness, 0.01, Some(0.0..=1.0));
+            ui.end_row();
+
+            ui.label("Dispersion:");
+            dispersion_combo_box(ui, index, dispersion);
+            ui.end_row();
+
+            match dispersion {
+                Some(DispersionModel::Cauchy { a, b }) => {
+                    ui.label("Cauchy A:");
+                    ui_for_number(ui, a, 0.001, Some(1.0..=f64::MAX));
+                    ui.end_row();
+
+                    ui.label("Cauchy B (µm²):");
+                    ui_for_number(ui, b, 0.0001, Some(0.0..=f64::MAX));
+                    ui.end_row();
+                }
+                Some(DispersionModel::Sellmeier { b, c }) => {
+                    ui.label("Sellmeier B:");
+                    ui.horizontal(|ui| {
+                        ui_for_vector(ui, b, 0.001, None);
+                    });
+                    ui.end_row();
+
+                    ui.label("Sellmeier C (µm²):");
+                    ui.horizontal(|ui| {
+                        ui_for_vector(ui, c, 0.001, None);
+                    });
+                    ui.end_row();
+                }
+                None => {}
+            }
+        }
+        MaterialType::Conductor { eta, k, roughness } => {
+            ui.label("Preset:");
+            conductor_preset_combo_box(ui, index, eta, k);
+            ui.end_row();
+
+            ui.label("Eta (RGB):");
+            ui.horizontal(|ui| {
+                ui_for_vector(ui, eta, 0.01, Some(0.0..=f64::MAX));
+            });
+            ui.end_row();
+
+            ui.label("K (RGB):");
+            ui.horizontal(|ui| {
+                ui_for_vector(ui, k, 0.01, Some(0.0..=f64::MAX));
+            });
+            ui.end_row();
+
+            ui.label("Roughness:");
+            ui_for_number(ui, roughness, 0.01, Some(0.0..=1.0));
+            ui.end_row();
         }
+        MaterialType::Principled {
+            base_color,
+            metallic,
+            roughness,
+            specular,
+            clearcoat,
+            sheen,
+            transmission,
+        } => {
+            ui.label("Base color:");
+            ui.color_edit_button_rgb(base_color);
+            ui.end_row();
+
+            for (label, value) in [
+                ("Metallic:", metallic),
+                ("Roughness:", roughness),
+                ("Specular:", specular),
+                ("Clearcoat:", clearcoat),
+                ("Sheen:", sheen),
+                ("Transmission:", transmission),
+            ] {
+                ui.label(label);
+                ui_for_number(ui, value, 0.01, Some(0.0..=1.0));
+                ui.end_row();
+            }
+        }
+        MaterialType::Mix {
+            first,
+            second,
+            weight,
+        } => {
+            ui.label("First:");
+            material_id_combo_box(ui, index, "first", material_ids, first);
+            ui.end_row();
+
+            ui.label("Second:");
+            material_id_combo_box(ui, index, "second", material_ids, second);
+            ui.end_row();
+
+            ui.label("Weight:");
+            texture_combo_box(ui, index, weight);
+            ui.end_row();
+
+            match weight {
+                TextureModel::Constant(value) => {
+                    ui.label("Value:");
+                    ui_for_number(ui, value, 0.01, Some(0.0..=1.0));
+                    ui.end_row();
+                }
+                TextureModel::Procedural(ProceduralTextureModel::Checker {
+                    scale,
+                    even,
+                    odd,
+                }) => {
+                    ui.label("Scale:");
+                    ui_for_number(ui, scale, 0.1, Some(0.001..=f64::MAX));
+                    ui.end_row();
+
+                    ui.label("Even:");
+                    ui_for_number(ui, even, 0.01, Some(0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label("Odd:");
+                    ui_for_number(ui, odd, 0.01, Some(0.0..=1.0));
+                    ui.end_row();
+                }
+            }
+        }
+        MaterialType::Coated {
+            base,
+            refraction_index,
+            roughness,
+            tint,
+        } => {
+            ui.label("Base:");
+            material_id_combo_box(ui, index, "base", material_ids, base);
+            ui.end_row();
+
+            ui.label("Refraction index:");
+            ui_for_number(ui, refraction_index, 0.1, Some(1.0..=f64::MAX));
+            ui.end_row();
+
+            ui.label("Roughness:");
+            ui_for_number(ui, roughness, 0.01, Some(0.0..=1.0));
+            ui.end_row();
+
+            ui.label("Tint:");
+            ui.color_edit_button_rgb(tint);
+            ui.end_row();
+        }
+    };
+}
+
+fn material_id_combo_box(
+    ui: &mut egui::Ui,
+    index: usize,
+    name: &str,
+    material_ids: &[String],
+    selected_id: &mut String,
+) {
+    egui::ComboBox::new(format!("material_{}_combo_box_{}", name, index), "")
+        .selected_text(format!("{:?}", selected_id))
+        .show_ui(ui, |ui| {
+            for material_id in material_ids {
+                ui.selectable_value(
+                    selected_id,
+                    material_id.clone(),
+                    material_id,
+                );
+            }
+        });
+}
+
+fn texture_combo_box(
+    ui: &mut egui::Ui,
+    index: usize,
+    texture: &mut TextureModel,
+) {
+    let is_constant = matches!(texture, TextureModel::Constant(_));
+
+    egui::ComboBox::new(format!("texture_combo_box_{}", index), "")
+        .selected_text(if is_constant { "Constant" } else { "Checker" })
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(is_constant, "Constant").clicked()
+                && !is_constant
+            {
+                *texture = TextureModel::Constant(0.5);
+            }
+            if ui.selectable_label(!is_constant, "Checker").clicked()
+                && is_constant
+            {
+                *texture =
+                    TextureModel::Procedural(ProceduralTextureModel::Checker {
+                        scale: 1.0,
+                        even: 0.0,
+                        odd: 1.0,
+                    });
+            }
+        });
+}
+
+fn dispersion_combo_box(
+    ui: &mut egui::Ui,
+    index: usize,
+    dispersion: &mut Option<DispersionModel>,
+) {
+    let name = |dispersion: &Option<DispersionModel>| match dispersion {
+        Some(DispersionModel::Cauchy { .. }) => "Cauchy",
+        Some(DispersionModel::Sellmeier { .. }) => "Sellmeier",
+        None => "None",
     };
+
+    egui::ComboBox::new(format!("dispersion_combo_box_{}", index), "")
+        .selected_text(name(dispersion))
+        .show_ui(ui, |ui| {
+            for option in [
+                None,
+                Some(DispersionModel::from(Dispersion::BK7_CAUCHY)),
+                Some(DispersionModel::from(Dispersion::BK7_SELLMEIER)),
+            ] {
+                if ui
+                    .selectable_label(
+                        name(dispersion) == name(&option),
+                        name(&option),
+                    )
+                    .clicked()
+                    && name(dispersion) != name(&option)
+                {
+                    *dispersion = option;
+                }
+            }
+        });
+}
+
+fn conductor_preset_combo_box(
+    ui: &mut egui::Ui,
+    index: usize,
+    eta: &mut [f64; 3],
+    k: &mut [f64; 3],
+) {
+    let selected = ConductorPreset::ALL
+        .into_iter()
+        .find(|preset| preset.eta() == *eta && preset.k() == *k);
+
+    egui::ComboBox::new(format!("conductor_preset_combo_box_{}", index), "")
+        .selected_text(match selected {
+            Some(preset) => preset.to_string(),
+            None => "custom".to_string(),
+        })
+        .show_ui(ui, |ui| {
+            for preset in ConductorPreset::ALL {
+                if ui
+                    .selectable_label(selected == Some(preset), preset.name())
+                    .clicked()
+                {
+                    *eta = preset.eta();
+                    *k = preset.k();
+                }
+            }
+        });
 }
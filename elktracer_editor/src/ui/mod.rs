@@ -1,8 +1,10 @@
 pub mod camera;
 pub mod material;
+pub mod picking;
 pub mod preview;
 pub mod rendering;
 pub mod scene_objects;
+pub mod statistics;
 
 use std::ops::RangeInclusive;
 
@@ -73,6 +75,24 @@ pub fn ui_for_vector(
     });
 }
 
+pub fn ui_for_integrator(
+    ui: &mut egui::Ui,
+    id: &str,
+    render_options: &mut elktracer_core::RenderOptions,
+) {
+    egui::ComboBox::new(id, "")
+        .selected_text(render_options.integrator.to_string())
+        .show_ui(ui, |ui| {
+            for integrator in elktracer_core::IntegratorType::ALL {
+                ui.selectable_value(
+                    &mut render_options.integrator,
+                    integrator,
+                    integrator.to_string(),
+                );
+            }
+        });
+}
+
 pub fn ui_for_string(
     ui: &mut egui::Ui,
     value: &mut String,
@@ -0,0 +1,71 @@
+use bevy::log::{info, warn};
+use bevy_egui::egui;
+use elktracer_core::{Camera, pick_object};
+use elktracer_json::{get_scene_objects, model::SceneModel};
+
+/// Selects the object under a click on a rendered image, and focuses the
+/// camera on the object under a secondary click. `image_offset` is the
+/// position of the shown image inside the full image in pixels and
+/// `image_scale` the size of a pixel on screen.
+pub fn handle_image_picking(
+    response: &egui::Response,
+    image_offset: egui::Vec2,
+    image_scale: f32,
+    scene_model: &mut SceneModel,
+    render_options: &elktracer_core::RenderOptions,
+    selected_object: &mut Option<usize>,
+) {
+    let select = response.clicked();
+    let focus = response.secondary_clicked();
+    if !(select || focus) {
+        return;
+    }
+    let Some(pointer) = response.interact_pointer_pos() else {
+        return;
+    };
+
+    // Pixel centers lie at integer image positions.
+    let position = (pointer - response.rect.min) / image_scale + image_offset;
+    let image_position = (position.x as f64 - 0.5, position.y as f64 - 0.5);
+
+    let camera = match Camera::try_from(scene_model.camera.clone()) {
+        Ok(camera) => camera,
+        Err(error) => {
+            warn!("Unable to pick object: {}", error);
+            return;
+        }
+    };
+    let objects = match get_scene_objects(scene_model) {
+        Ok(objects) => objects,
+        Err(error) => {
+            warn!("Unable to pick object: {}", error);
+            return;
+        }
+    };
+    let picked =
+        match pick_object(&camera, &objects, render_options, image_position) {
+            Ok(picked) => picked,
+            Err(error) => {
+                warn!("Unable to pick object: {}", error);
+                return;
+            }
+        };
+
+    if select {
+        *selected_object = picked.as_ref().map(|picked| picked.object_index);
+        match selected_object.and_then(|index| scene_model.objects.get(index)) {
+            Some(object) => info!("Selected {:?}", object.id),
+            None => info!("Cleared selection"),
+        }
+    }
+    if focus && let Some(picked) = picked {
+        // The focus plane is perpendicular to the view direction.
+        let view_direction = (camera.look_at - camera.position).unit();
+        let focus_distance =
+            (picked.point - camera.position).dot(view_direction);
+        if focus_distance > 0.0 {
+            scene_model.camera.focus_distance = focus_distance;
+            info!("Focused at {:.3}", focus_distance);
+        }
+    }
+}
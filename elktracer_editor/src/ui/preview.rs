@@ -7,19 +7,27 @@ use elktracer_json::model::SceneModel;
 
 use crate::render_tasks::{ElktracerRenderSystem, spawn_render_task};
 
+use super::{picking::handle_image_picking, ui_for_integrator};
+
 pub fn ui_for_preview(
     ui: &mut egui::Ui,
     world: &mut World,
-    scene_model: &SceneModel,
+    scene_model: &mut SceneModel,
     render_options: &mut elktracer_core::RenderOptions,
+    selected_object: &mut Option<usize>,
 ) {
     let mut render_system: Mut<ElktracerRenderSystem> = world.resource_mut();
     let key = "Preview";
 
-    let button = ui.add_enabled(
-        !render_system.tasks.contains_key(key),
-        Button::new("Render"),
-    );
+    let button = ui
+        .horizontal(|ui| {
+            ui_for_integrator(ui, "preview_integrator", render_options);
+            ui.add_enabled(
+                !render_system.tasks.contains_key(key),
+                Button::new("Render"),
+            )
+        })
+        .inner;
     if button.clicked() {
         let task = spawn_render_task(scene_model.clone(), *render_options);
         info!("Insert {} task", key);
@@ -27,15 +35,27 @@ pub fn ui_for_preview(
     }
 
     if let Some(preview_texture) = render_system.texture_ids.get(key) {
-        ui.add(egui::widgets::Image::new(egui::load::SizedTexture::new(
-            *preview_texture,
-            [
-                // render_options.image_width as f32,
-                // (render_options.image_width as f64
-                //     / render_options.aspect_ratio) as f32,
-                ui.available_width(),
-                ui.available_width() / (render_options.aspect_ratio as f32),
-            ],
-        )));
+        let response = ui.add(
+            egui::widgets::Image::new(egui::load::SizedTexture::new(
+                *preview_texture,
+                [
+                    // render_options.image_width as f32,
+                    // (render_options.image_width as f64
+                    //     / render_options.aspect_ratio) as f32,
+                    ui.available_width(),
+                    ui.available_width() / (render_options.aspect_ratio as f32),
+                ],
+            ))
+            .sense(egui::Sense::click()),
+        );
+        // The preview is scaled to the width of the window.
+        handle_image_picking(
+            &response,
+            egui::Vec2::ZERO,
+            response.rect.width() / render_options.image_width as f32,
+            scene_model,
+            render_options,
+            selected_object,
+        );
     }
 }
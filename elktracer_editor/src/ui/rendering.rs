@@ -1,16 +1,22 @@
 use bevy::prelude::*;
-use bevy_egui::egui::{self, DragValue, Grid};
+use bevy_egui::egui::{
+    self, Color32, DragValue, Grid, Sense, Stroke, StrokeKind,
+};
+use elktracer_core::{FilterType, RenderRegion};
 use elktracer_json::model::SceneModel;
 
 use crate::render_tasks::{ElktracerRenderSystem, spawn_render_task};
 
+use super::{picking::handle_image_picking, ui_for_integrator};
+
 const NUMBER_INPUT_SIZE: [f32; 2] = [60.0, 18.0];
 
 pub fn ui_for_rendering_options(
     ui: &mut egui::Ui,
     world: &mut World,
-    scene_model: &SceneModel,
+    scene_model: &mut SceneModel,
     render_options: &mut elktracer_core::RenderOptions,
+    selected_object: &mut Option<usize>,
 ) {
     let mut render_system: Mut<ElktracerRenderSystem> = world.resource_mut();
     let key = "Rendering";
@@ -52,6 +58,59 @@ pub fn ui_for_rendering_options(
                 );
                 ui.end_row();
 
+                ui.label("Russian Roulette Depth:");
+                ui.add_sized(
+                    NUMBER_INPUT_SIZE,
+                    DragValue::new(
+                        &mut render_options.russian_roulette_min_depth,
+                    )
+                    .range(0..=u16::MAX),
+                );
+                ui.end_row();
+
+                ui.label("Integrator:");
+                ui_for_integrator(ui, "rendering_integrator", render_options);
+                ui.end_row();
+
+                ui.label("Spectral:");
+                ui.checkbox(&mut render_options.spectral, "");
+                ui.end_row();
+
+                ui.label("Filter:");
+                ui_for_filter(ui, render_options);
+                ui.end_row();
+
+                ui.label("Filter Radius:");
+                ui.add_sized(
+                    NUMBER_INPUT_SIZE,
+                    DragValue::new(&mut render_options.filter_radius)
+                        .speed(0.05)
+                        .range(0.5..=4.0),
+                );
+                ui.end_row();
+
+                ui.label("Region:");
+                ui.horizontal(|ui| {
+                    match render_options.region {
+                        Some(region) => ui.label(region.to_string()),
+                        None => ui.label("Drag on image"),
+                    };
+                    if ui
+                        .add_enabled(
+                            render_options.region.is_some(),
+                            egui::Button::new("Clear"),
+                        )
+                        .clicked()
+                    {
+                        render_options.region = None;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Crop to Region:");
+                ui.checkbox(&mut render_options.crop_to_region, "");
+                ui.end_row();
+
                 if ui
                     .add_sized(
                         egui::Vec2::new(ui.available_width(), 18.0),
@@ -69,15 +128,113 @@ pub fn ui_for_rendering_options(
     });
 
     if let Some(preview_texture) = render_system.texture_ids.get(key) {
-        ui.add(egui::widgets::Image::new(egui::load::SizedTexture::new(
+        ui_for_rendered_image(
+            ui,
             *preview_texture,
-            [
-                // ui.available_width(),
-                // ui.available_width() / (render_options.aspect_ratio as f32),
-                render_options.image_width as f32,
-                (render_options.image_width as f64
-                    / render_options.aspect_ratio) as f32,
-            ],
-        )));
+            scene_model,
+            render_options,
+            selected_object,
+        );
+    }
+}
+
+fn ui_for_filter(
+    ui: &mut egui::Ui,
+    render_options: &mut elktracer_core::RenderOptions,
+) {
+    egui::ComboBox::new("rendering_filter", "")
+        .selected_text(render_options.filter.to_string())
+        .show_ui(ui, |ui| {
+            for filter in FilterType::ALL {
+                if ui
+                    .selectable_value(
+                        &mut render_options.filter,
+                        filter,
+                        filter.to_string(),
+                    )
+                    .changed()
+                {
+                    render_options.filter_radius = filter.default_radius();
+                }
+            }
+        });
+}
+
+/// Shows the rendered image, on which a render region can be selected by
+/// dragging and objects by clicking.
+fn ui_for_rendered_image(
+    ui: &mut egui::Ui,
+    texture: egui::TextureId,
+    scene_model: &mut SceneModel,
+    render_options: &mut elktracer_core::RenderOptions,
+    selected_object: &mut Option<usize>,
+) {
+    let image_width = render_options.image_width;
+    let image_height = (render_options.image_width as f64
+        / render_options.aspect_ratio) as u32;
+    let (image_offset, image_size) = match render_options.region {
+        Some(region) if render_options.crop_to_region => {
+            let region = region.clamp(image_width, image_height);
+            (
+                egui::vec2(region.x as f32, region.y as f32),
+                [region.width as f32, region.height as f32],
+            )
+        }
+        _ => (egui::Vec2::ZERO, [image_width as f32, image_height as f32]),
+    };
+
+    let response = ui.add(
+        egui::widgets::Image::new(egui::load::SizedTexture::new(
+            texture, image_size,
+        ))
+        .sense(Sense::click_and_drag()),
+    );
+    handle_image_picking(
+        &response,
+        image_offset,
+        1.0,
+        scene_model,
+        render_options,
+        selected_object,
+    );
+
+    // Regions can only be selected on the full image.
+    if render_options.crop_to_region && render_options.region.is_some() {
+        return;
+    }
+
+    let drag_start_id = response.id.with("render_region_drag_start");
+    if response.drag_started()
+        && let Some(position) = response.interact_pointer_pos()
+    {
+        ui.data_mut(|data| data.insert_temp(drag_start_id, position));
+    }
+    if response.dragged()
+        && let Some(start) =
+            ui.data(|data| data.get_temp::<egui::Pos2>(drag_start_id))
+        && let Some(position) = response.interact_pointer_pos()
+    {
+        let selection = egui::Rect::from_two_pos(start, position)
+            .intersect(response.rect)
+            .translate(-response.rect.min.to_vec2());
+        render_options.region = Some(RenderRegion::new(
+            selection.min.x as u32,
+            selection.min.y as u32,
+            selection.width().round() as u32,
+            selection.height().round() as u32,
+        ));
+    }
+
+    if let Some(region) = render_options.region {
+        let rect = egui::Rect::from_min_size(
+            response.rect.min + egui::vec2(region.x as f32, region.y as f32),
+            egui::vec2(region.width as f32, region.height as f32),
+        );
+        ui.painter().rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.0, Color32::YELLOW),
+            StrokeKind::Outside,
+        );
     }
 }
@@ -1,12 +1,17 @@
 use bevy_egui::egui::{self, Button, Color32, Grid, Stroke};
-use elktracer_json::model::{MaterialModel, ObjectModel, ObjectType};
+use elktracer_json::model::{
+    CsgOperationModel, GroupModel, KeyframeModel, MaterialModel, MotionModel,
+    ObjectModel, ObjectType,
+};
 
 use super::{ui_for_number, ui_for_string, ui_for_vector};
 
 pub fn ui_for_scene_objects(
     ui: &mut egui::Ui,
     materials: &Vec<MaterialModel>,
+    groups: &[GroupModel],
     scene_objects: &mut Vec<ObjectModel>,
+    selected_object: &mut Option<usize>,
 ) {
     ui.add_space(4.0);
 
@@ -19,24 +24,49 @@ pub fn ui_for_scene_objects(
                 material_id = first.id.clone();
             }
 
-            scene_objects.push(ObjectModel {
-                id: "New Sphere".to_string(),
-                position: [0.0, 0.0, 0.0],
-                material_id,
-                object_type: ObjectType::Sphere { radius: 1.0 },
-            });
+            scene_objects.push(ObjectModel::new(
+                "New Sphere",
+                [0.0, 0.0, 0.0],
+                &material_id,
+                ObjectType::Sphere { radius: 1.0 },
+            ));
+        }
+
+        if ui
+            .add_enabled(!groups.is_empty(), Button::new("Add Instance"))
+            .clicked()
+            && let Some(first) = groups.first()
+        {
+            scene_objects.push(ObjectModel::new(
+                "New Instance",
+                [0.0, 0.0, 0.0],
+                "",
+                ObjectType::Instance {
+                    group: first.id.clone(),
+                },
+            ));
         }
     });
 
     let mut to_be_removed: Option<usize> = None;
 
+    // Scrolls to an object once after it was picked.
+    let scrolled_id = ui.id().with("scrolled_selected_object");
+    let scrolled_object =
+        ui.data(|data| data.get_temp::<Option<usize>>(scrolled_id));
+
     for (index, scene_object) in scene_objects.iter_mut().enumerate() {
         let mut remove = false;
+        let is_selected = *selected_object == Some(index);
 
-        egui::Frame::new()
+        let frame = egui::Frame::new()
             .inner_margin(6.0)
             .outer_margin(6.0)
-            .stroke(Stroke::new(1.0, Color32::from_rgb(150, 150, 150)))
+            .stroke(if is_selected {
+                Stroke::new(2.0, Color32::YELLOW)
+            } else {
+                Stroke::new(1.0, Color32::from_rgb(150, 150, 150))
+            })
             .corner_radius(2.0)
             .show(ui, |ui| {
                 // Set a fixed width
@@ -51,18 +81,28 @@ pub fn ui_for_scene_objects(
                             scene_object,
                             &mut remove,
                             materials,
+                            groups,
                         );
                     },
                 );
             });
 
+        if is_selected && scrolled_object != Some(*selected_object) {
+            frame.response.scroll_to_me(None);
+        }
         if remove {
             to_be_removed = Some(index);
         }
     }
+    ui.data_mut(|data| data.insert_temp(scrolled_id, *selected_object));
 
     if let Some(remove) = to_be_removed {
         scene_objects.remove(remove);
+        *selected_object = match *selected_object {
+            Some(index) if index == remove => None,
+            Some(index) if index > remove => Some(index - 1),
+            selected => selected,
+        };
     }
 }
 
@@ -72,12 +112,19 @@ fn ui_for_object(
     scene_object: &mut ObjectModel,
     should_be_removed: &mut bool,
     materials: &Vec<MaterialModel>,
+    groups: &[GroupModel],
 ) {
     ui.horizontal(|ui| {
         match scene_object.object_type {
             ObjectType::Sphere { .. } => {
                 ui.label("Sphere");
             }
+            ObjectType::Instance { .. } => {
+                ui.label("Instance");
+            }
+            ObjectType::Csg { .. } => {
+                ui.label("CSG");
+            }
         };
 
         ui_for_string(ui, &mut scene_object.id, "Scene Object ID");
@@ -100,16 +147,38 @@ fn ui_for_object(
                 });
                 ui.end_row();
 
-                ui.label("Material:");
-                material_combo_box(
-                    ui,
-                    index,
-                    materials,
-                    &mut scene_object.material_id,
-                );
+                ui.label("Rotation °:");
+                ui.horizontal(|ui| {
+                    ui_for_vector(ui, &mut scene_object.rotation, 1.0, None);
+                });
+                ui.end_row();
+
+                ui.label("Scale:");
+                ui.horizontal(|ui| {
+                    ui_for_vector(ui, &mut scene_object.scale, 0.01, None);
+                });
+                ui.end_row();
+
+                if let ObjectType::Sphere { .. } = scene_object.object_type {
+                    ui.label("Material:");
+                    material_combo_box(
+                        ui,
+                        index,
+                        materials,
+                        &mut scene_object.material_id,
+                    );
+                    ui.end_row();
+                }
+
+                ui_for_object_type(ui, index, scene_object, groups);
+
+                ui.label("Motion:");
+                motion_combo_box(ui, index, scene_object);
                 ui.end_row();
 
-                ui_for_object_type(ui, scene_object);
+                if let Some(motion) = &mut scene_object.motion {
+                    ui_for_motion(ui, motion);
+                }
             });
     });
 }
@@ -133,12 +202,147 @@ fn material_combo_box(
         });
 }
 
-fn ui_for_object_type(ui: &mut egui::Ui, scene_object: &mut ObjectModel) {
+fn ui_for_object_type(
+    ui: &mut egui::Ui,
+    index: usize,
+    scene_object: &mut ObjectModel,
+    groups: &[GroupModel],
+) {
     match &mut scene_object.object_type {
         ObjectType::Sphere { radius } => {
             ui.label("Radius:");
             ui_for_number(ui, radius, 0.1, None);
             ui.end_row();
         }
+        ObjectType::Instance { group } => {
+            ui.label("Group:");
+            egui::ComboBox::new(format!("group_combo_box_{}", index), "")
+                .selected_text(format!("{:?}", group))
+                .show_ui(ui, |ui| {
+                    for group_model in groups {
+                        ui.selectable_value(
+                            group,
+                            group_model.id.clone(),
+                            &group_model.id,
+                        );
+                    }
+                });
+            ui.end_row();
+        }
+        ObjectType::Csg {
+            operation,
+            first,
+            second,
+        } => {
+            ui.label("Operation:");
+            egui::ComboBox::new(format!("csg_combo_box_{}", index), "")
+                .selected_text(format!("{:?}", operation))
+                .show_ui(ui, |ui| {
+                    for value in [
+                        CsgOperationModel::Union,
+                        CsgOperationModel::Intersection,
+                        CsgOperationModel::Difference,
+                    ] {
+                        ui.selectable_value(
+                            operation,
+                            value,
+                            format!("{:?}", value),
+                        );
+                    }
+                });
+            ui.end_row();
+
+            // The combined objects are edited in the scene file.
+            ui.label("Objects:");
+            ui.label(format!("{:?}, {:?}", first.id, second.id));
+            ui.end_row();
+        }
+    }
+}
+
+fn motion_combo_box(
+    ui: &mut egui::Ui,
+    index: usize,
+    scene_object: &mut ObjectModel,
+) {
+    let selected = match scene_object.motion {
+        None => "None",
+        Some(MotionModel::Linear { .. }) => "Linear",
+        Some(MotionModel::Keyframes { .. }) => "Keyframes",
+    };
+
+    egui::ComboBox::new(format!("motion_combo_box_{}", index), "")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(selected == "None", "None").clicked() {
+                scene_object.motion = None;
+            }
+            if ui
+                .selectable_label(selected == "Linear", "Linear")
+                .clicked()
+                && selected != "Linear"
+            {
+                scene_object.motion = Some(MotionModel::Linear {
+                    velocity: [0.0, 0.0, 0.0],
+                });
+            }
+            if ui
+                .selectable_label(selected == "Keyframes", "Keyframes")
+                .clicked()
+                && selected != "Keyframes"
+            {
+                scene_object.motion = Some(MotionModel::Keyframes {
+                    keyframes: vec![KeyframeModel {
+                        time: 0.0,
+                        position: scene_object.position,
+                    }],
+                });
+            }
+        });
+}
+
+fn ui_for_motion(ui: &mut egui::Ui, motion: &mut MotionModel) {
+    match motion {
+        MotionModel::Linear { velocity } => {
+            ui.label("Velocity:");
+            ui.horizontal(|ui| {
+                ui_for_vector(ui, velocity, 0.1, None);
+            });
+            ui.end_row();
+        }
+        MotionModel::Keyframes { keyframes } => {
+            let mut to_be_removed: Option<usize> = None;
+
+            for (index, keyframe) in keyframes.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label("Time:");
+                    ui_for_number(ui, &mut keyframe.time, 0.01, None);
+                    if ui.button("Delete").clicked() {
+                        to_be_removed = Some(index);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui_for_vector(ui, &mut keyframe.position, 0.1, None);
+                });
+                ui.end_row();
+            }
+
+            if let Some(remove) = to_be_removed {
+                keyframes.remove(remove);
+            }
+
+            ui.label("");
+            if ui.button("Add Keyframe").clicked() {
+                let last = keyframes.last().copied().unwrap_or(KeyframeModel {
+                    time: 0.0,
+                    position: [0.0, 0.0, 0.0],
+                });
+                keyframes.push(KeyframeModel {
+                    time: last.time + 1.0,
+                    position: last.position,
+                });
+            }
+            ui.end_row();
+        }
     }
 }
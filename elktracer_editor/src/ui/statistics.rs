@@ -0,0 +1,68 @@
+use bevy::ecs::world::World;
+use bevy_egui::egui;
+
+use crate::render_tasks::ElktracerRenderSystem;
+
+pub fn ui_for_render_stats(ui: &mut egui::Ui, world: &mut World) {
+    let render_system = world.resource::<ElktracerRenderSystem>();
+
+    for key in ["Rendering", "Preview"] {
+        ui.heading(key);
+        let Some(stats) = render_system.stats.get(key) else {
+            ui.label("Not rendered yet");
+            continue;
+        };
+
+        egui::Grid::new(format!("{}_stats", key))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Wall time");
+                ui.label(format!("{:.3} s", stats.wall_time.as_secs_f64()));
+                ui.end_row();
+
+                ui.label("CPU time");
+                ui.label(match stats.cpu_time {
+                    Some(cpu_time) => {
+                        format!("{:.3} s", cpu_time.as_secs_f64())
+                    }
+                    None => "n/a".to_string(),
+                });
+                ui.end_row();
+
+                ui.label("Primary rays");
+                ui.label(stats.primary_rays.to_string());
+                ui.end_row();
+
+                ui.label("Secondary rays");
+                ui.label(stats.secondary_rays.to_string());
+                ui.end_row();
+
+                ui.label("Rays per second");
+                ui.label(format!("{:.0}", stats.rays_per_second()));
+                ui.end_row();
+
+                ui.label("Object tests per ray");
+                ui.label(format!("{:.2}", stats.object_tests_per_ray()));
+                ui.end_row();
+
+                ui.label("Invalid samples");
+                ui.label(stats.invalid_samples.to_string());
+                ui.end_row();
+            });
+
+        ui.collapsing(format!("{} path lengths", key), |ui| {
+            egui::Grid::new(format!("{}_path_lengths", key))
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (length, count) in
+                        stats.path_length_histogram.iter().enumerate()
+                    {
+                        ui.label(length.to_string());
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+        ui.separator();
+    }
+}
@@ -0,0 +1,295 @@
+use elktracer_core::{Error, Interpolation, error};
+
+use crate::model::{
+    AnimationKeyframeModel, AnimationTargetModel, AnimationValueModel,
+    CameraPropertyModel, ObjectPropertyModel, ObjectType, SceneModel,
+};
+
+/// Returns the scene with all animated properties set to their values at
+/// `frame`. Scenes without animation are returned unchanged.
+pub fn scene_at_frame(
+    scene: &SceneModel,
+    frame: f64,
+) -> error::Result<SceneModel> {
+    let mut scene_at_frame = scene.clone();
+    let Some(animation) = &scene.animation else {
+        return Ok(scene_at_frame);
+    };
+    validate_animation(scene)?;
+
+    for track in animation.tracks.iter() {
+        let Some(value) = evaluate_keyframes(&track.keyframes, frame) else {
+            continue;
+        };
+        apply_value(&mut scene_at_frame, &track.target, value);
+    }
+
+    Ok(scene_at_frame)
+}
+
+/// Checks that every track targets an existing property and that all of
+/// its keyframe values have the type of the property, so that an image
+/// sequence fails before its first frame instead of partway through.
+pub fn validate_animation(scene: &SceneModel) -> error::Result<()> {
+    let Some(animation) = &scene.animation else {
+        return Ok(());
+    };
+
+    for track in animation.tracks.iter() {
+        let expects_vector = match &track.target {
+            AnimationTargetModel::Camera { property } => matches!(
+                property,
+                CameraPropertyModel::Position
+                    | CameraPropertyModel::LookAt
+                    | CameraPropertyModel::Up
+            ),
+            AnimationTargetModel::Object { id, property } => {
+                let object = scene
+                    .objects
+                    .iter()
+                    .find(|object| &object.id == id)
+                    .ok_or_else(|| {
+                        Error::InvalidAnimation(format!(
+                            "Animated object {:?} not found",
+                            id
+                        ))
+                    })?;
+                match property {
+                    ObjectPropertyModel::Position => true,
+                    ObjectPropertyModel::Radius => {
+                        if !matches!(
+                            object.object_type,
+                            ObjectType::Sphere { .. }
+                        ) {
+                            return Err(Error::InvalidAnimation(format!(
+                                "Animated object {:?} has no radius",
+                                id
+                            )));
+                        }
+                        false
+                    }
+                }
+            }
+        };
+
+        let mismatch = track.keyframes.iter().find(|keyframe| {
+            matches!(keyframe.value, AnimationValueModel::Vector(_))
+                != expects_vector
+        });
+        if let Some(keyframe) = mismatch {
+            return Err(Error::InvalidAnimation(format!(
+                "Keyframe at frame {} of {:?} must be a {}",
+                keyframe.frame,
+                track.target,
+                if expects_vector { "vector" } else { "number" }
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn evaluate_keyframes(
+    keyframes: &[AnimationKeyframeModel],
+    frame: f64,
+) -> Option<AnimationValueModel> {
+    let mut keyframes: Vec<&AnimationKeyframeModel> =
+        keyframes.iter().collect();
+    keyframes.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+
+    let first = keyframes.first()?;
+    let next = keyframes.iter().position(|keyframe| keyframe.frame > frame);
+
+    let value = match next {
+        Some(0) => first.value,
+        Some(index) => {
+            let from = keyframes[index - 1];
+            let to = keyframes[index];
+            let t = Interpolation::from(from.interpolation)
+                .ease((frame - from.frame) / (to.frame - from.frame));
+            interpolate(from.value, to.value, t)
+        }
+        None => keyframes[keyframes.len() - 1].value,
+    };
+    Some(value)
+}
+
+/// Values of different types are ruled out by `validate_animation`.
+fn interpolate(
+    from: AnimationValueModel,
+    to: AnimationValueModel,
+    t: f64,
+) -> AnimationValueModel {
+    match (from, to) {
+        (AnimationValueModel::Scalar(a), AnimationValueModel::Scalar(b)) => {
+            AnimationValueModel::Scalar(a + (b - a) * t)
+        }
+        (AnimationValueModel::Vector(a), AnimationValueModel::Vector(b)) => {
+            AnimationValueModel::Vector([
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+            ])
+        }
+        _ => from,
+    }
+}
+
+/// Sets the property of a track checked by `validate_animation`.
+fn apply_value(
+    scene: &mut SceneModel,
+    target: &AnimationTargetModel,
+    value: AnimationValueModel,
+) {
+    match target {
+        AnimationTargetModel::Camera { property } => {
+            let camera = &mut scene.camera;
+            match property {
+                CameraPropertyModel::Position => {
+                    set_vector(&mut camera.position, value)
+                }
+                CameraPropertyModel::LookAt => {
+                    set_vector(&mut camera.look_at, value)
+                }
+                CameraPropertyModel::Up => set_vector(&mut camera.up, value),
+                CameraPropertyModel::FovVerticalDegrees => set_scalar(
+                    camera.fov_vertical_degrees.get_or_insert(0.0),
+                    value,
+                ),
+                CameraPropertyModel::DefocusAngle => {
+                    set_scalar(&mut camera.defocus_angle, value)
+                }
+                CameraPropertyModel::FocusDistance => {
+                    set_scalar(&mut camera.focus_distance, value)
+                }
+            }
+        }
+        AnimationTargetModel::Object { id, property } => {
+            let Some(object) =
+                scene.objects.iter_mut().find(|object| &object.id == id)
+            else {
+                return;
+            };
+            match property {
+                ObjectPropertyModel::Position => {
+                    set_vector(&mut object.position, value)
+                }
+                ObjectPropertyModel::Radius => {
+                    if let ObjectType::Sphere { radius } =
+                        &mut object.object_type
+                    {
+                        set_scalar(radius, value)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn set_scalar(property: &mut f64, value: AnimationValueModel) {
+    if let AnimationValueModel::Scalar(value) = value {
+        *property = value;
+    }
+}
+
+fn set_vector(property: &mut [f64; 3], value: AnimationValueModel) {
+    if let AnimationValueModel::Vector(value) = value {
+        *property = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_util::{scene_json, sphere_json};
+
+    fn animated_scene(tracks: serde_json::Value) -> SceneModel {
+        let mut scene =
+            scene_json(json!([sphere_json("ball", [0.0; 3], "gray")]));
+        scene["animation"] = json!({ "tracks": tracks });
+        serde_json::from_value(scene).unwrap()
+    }
+
+    fn position_track(keyframes: serde_json::Value) -> serde_json::Value {
+        json!([{
+            "target": { "type": "Object", "id": "ball", "property": "position" },
+            "keyframes": keyframes
+        }])
+    }
+
+    #[test]
+    fn scene_at_frame_should_hold_and_interpolate_keyframes() {
+        let scene = animated_scene(position_track(json!([
+            { "frame": 10, "value": [0, 0, 0] },
+            { "frame": 20, "value": [10, 0, 0] },
+            {
+                "frame": 30,
+                "value": [10, 10, 0],
+                "interpolation": { "type": "Bezier" }
+            }
+        ])));
+        let x_at = |frame: f64| {
+            let scene = scene_at_frame(&scene, frame).unwrap();
+            scene.objects[0].position
+        };
+
+        assert_eq!(x_at(0.0), [0.0, 0.0, 0.0]);
+        assert_eq!(x_at(10.0), [0.0, 0.0, 0.0]);
+        assert_eq!(x_at(15.0), [5.0, 0.0, 0.0]);
+        assert_eq!(x_at(20.0), [10.0, 0.0, 0.0]);
+        assert_eq!(x_at(25.0), [10.0, 5.0, 0.0]);
+        assert_eq!(x_at(30.0), [10.0, 10.0, 0.0]);
+        assert_eq!(x_at(40.0), [10.0, 10.0, 0.0]);
+    }
+
+    #[test]
+    fn scene_at_frame_should_ease_bezier_keyframes() {
+        let scene = animated_scene(json!([{
+            "target": { "type": "Camera", "property": "focus-distance" },
+            "keyframes": [
+                {
+                    "frame": 0,
+                    "value": 0,
+                    "interpolation": { "type": "Bezier" }
+                },
+                { "frame": 100, "value": 100 }
+            ]
+        }]));
+        let distance_at = |frame: f64| {
+            scene_at_frame(&scene, frame).unwrap().camera.focus_distance
+        };
+
+        assert!(distance_at(10.0) < 10.0);
+        assert!((distance_at(50.0) - 50.0).abs() < 1e-6);
+        assert!(distance_at(90.0) > 90.0);
+    }
+
+    #[test]
+    fn validate_animation_should_reject_invalid_tracks() {
+        let invalid_tracks = [
+            position_track(json!([{ "frame": 0, "value": 1 }])),
+            position_track(json!([
+                { "frame": 0, "value": [0, 0, 0] },
+                { "frame": 10, "value": 1 }
+            ])),
+            json!([{
+                "target": { "type": "Object", "id": "cube", "property": "position" },
+                "keyframes": [{ "frame": 0, "value": [0, 0, 0] }]
+            }]),
+            json!([{
+                "target": { "type": "Camera", "property": "up" },
+                "keyframes": [{ "frame": 0, "value": 1 }]
+            }]),
+        ];
+
+        for tracks in invalid_tracks {
+            let scene = animated_scene(tracks);
+            assert!(matches!(
+                validate_animation(&scene),
+                Err(Error::InvalidAnimation(_))
+            ));
+            assert!(scene_at_frame(&scene, 0.0).is_err());
+        }
+    }
+}
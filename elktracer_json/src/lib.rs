@@ -1,64 +1,347 @@
+pub mod animation;
 pub mod model;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 use std::{
     collections::HashMap,
     fs::File,
     io::{BufReader, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
-use elktracer_core::{Color, RayHitTest, Vec3f};
-use model::SceneModel;
+use elktracer_core::{
+    CheckpointSettings, Color, CsgOperation, Dispersion, Error, Material,
+    Motion, RayHitTest, RenderStats, Texture, Transform, Vec3f, error,
+};
+use model::{
+    ApertureModel, MaterialModel, ObjectModel, ObjectType,
+    ProceduralTextureModel, RenderStatsModel, SceneModel, TextureModel,
+};
 
-pub fn get_scene_objects(scene: &SceneModel) -> Vec<Box<dyn RayHitTest>> {
-    let mut core_materials: HashMap<String, Arc<dyn elktracer_core::Material>> =
-        HashMap::new();
+/// Creates the core objects of the scene. Fails on references to missing
+/// materials or groups, reference cycles and invalid transforms.
+pub fn get_scene_objects(
+    scene: &SceneModel,
+) -> error::Result<Vec<Box<dyn RayHitTest>>> {
+    let mut core_materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
 
     for material in scene.materials.iter() {
-        core_materials.insert(
-            material.id.clone(),
-            match material.material_type {
-                crate::model::MaterialType::Lambert { albedo } => Arc::new(
-                    elktracer_core::LambertMaterial::new(Color::from(albedo)),
-                ),
-                crate::model::MaterialType::Metal { albedo, fuzziness } => {
-                    Arc::new(elktracer_core::MetalMaterial::new(
-                        Color::from(albedo),
-                        fuzziness,
-                    ))
-                }
-                crate::model::MaterialType::Transparent {
-                    refraction_index,
-                } => Arc::new(elktracer_core::TransparentMaterial::new(
-                    refraction_index,
-                )),
-            },
-        );
+        resolve_material(
+            &material.id,
+            &scene.materials,
+            &mut core_materials,
+            &mut Vec::new(),
+        )?;
     }
 
+    let mut objects = SceneObjects {
+        scene,
+        materials: core_materials,
+        groups: HashMap::new(),
+        resolving_groups: Vec::new(),
+    };
     scene
         .objects
         .iter()
-        .map(|scene_object| match scene_object.object_type {
-            crate::model::ObjectType::Sphere { radius } => {
-                Box::new(elktracer_core::Sphere::new(
-                    Vec3f::from(scene_object.position),
-                    radius,
-                    core_materials
-                        .get(&scene_object.material_id)
-                        .expect("Material not found")
-                        .clone(),
-                )) as Box<dyn RayHitTest>
+        .map(|scene_object| objects.create_object(scene_object))
+        .collect()
+}
+
+/// Creates the core objects of a scene, sharing the materials and the
+/// geometry of instanced groups.
+struct SceneObjects<'a> {
+    scene: &'a SceneModel,
+    materials: HashMap<String, Arc<dyn Material>>,
+    groups: HashMap<String, Arc<dyn RayHitTest>>,
+    resolving_groups: Vec<String>,
+}
+
+impl SceneObjects<'_> {
+    fn create_object(
+        &mut self,
+        scene_object: &ObjectModel,
+    ) -> error::Result<Box<dyn RayHitTest>> {
+        let [x, y, z] = scene_object.scale;
+        if x == 0.0 || y == 0.0 || z == 0.0 {
+            return Err(Error::ZeroObjectScale(scene_object.id.clone()));
+        }
+        let transform = Transform::new(
+            Vec3f::from(scene_object.position),
+            Vec3f::from(scene_object.rotation),
+            Vec3f::from(scene_object.scale),
+        );
+
+        Ok(match &scene_object.object_type {
+            ObjectType::Sphere { radius } if !scene_object.is_transformed() => {
+                Box::new(self.sphere(scene_object, *radius)?)
+            }
+            ObjectType::Sphere { radius } => {
+                self.warn_unsupported_motion(scene_object);
+                let sphere = elktracer_core::Sphere::new(
+                    Vec3f::zero(),
+                    *radius,
+                    self.material(&scene_object.material_id)?,
+                );
+                Box::new(elktracer_core::Instance::new(
+                    Arc::new(sphere),
+                    transform,
+                ))
+            }
+            ObjectType::Instance { group } => {
+                self.warn_unsupported_motion(scene_object);
+                Box::new(elktracer_core::Instance::new(
+                    self.group(group)?,
+                    transform,
+                ))
+            }
+            ObjectType::Csg {
+                operation,
+                first,
+                second,
+            } => {
+                self.warn_unsupported_motion(scene_object);
+                let csg = elktracer_core::Csg::new(
+                    CsgOperation::from(*operation),
+                    self.create_object(first)?,
+                    self.create_object(second)?,
+                );
+                if transform == Transform::identity() {
+                    Box::new(csg)
+                } else {
+                    Box::new(elktracer_core::Instance::new(
+                        Arc::new(csg),
+                        transform,
+                    ))
+                }
             }
         })
-        .collect()
+    }
+
+    /// Creates a sphere placed at the position of the object, which must not
+    /// be rotated or scaled.
+    fn sphere(
+        &self,
+        scene_object: &ObjectModel,
+        radius: f64,
+    ) -> error::Result<elktracer_core::Sphere> {
+        Ok(elktracer_core::Sphere::new(
+            Vec3f::from(scene_object.position),
+            radius,
+            self.material(&scene_object.material_id)?,
+        )
+        .with_motion(scene_object.motion.clone().map(Motion::from)))
+    }
+
+    fn material(&self, id: &str) -> error::Result<Arc<dyn Material>> {
+        self.materials
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::MaterialNotFound(id.to_string()))
+    }
+
+    /// Creates the group for `id` once, first creating the groups it
+    /// instances.
+    fn group(&mut self, id: &str) -> error::Result<Arc<dyn RayHitTest>> {
+        if let Some(group) = self.groups.get(id) {
+            return Ok(group.clone());
+        }
+        if self
+            .resolving_groups
+            .iter()
+            .any(|resolving_id| resolving_id == id)
+        {
+            return Err(Error::GroupReferenceCycle(format!(
+                "{} -> {}",
+                self.resolving_groups.join(" -> "),
+                id
+            )));
+        }
+
+        let scene = self.scene;
+        let group_model = scene
+            .groups
+            .iter()
+            .find(|group| group.id == id)
+            .ok_or_else(|| Error::GroupNotFound(id.to_string()))?;
+        self.resolving_groups.push(id.to_string());
+        let objects = self.create_group_objects(&group_model.objects);
+        self.resolving_groups.pop();
+
+        let group: Arc<dyn RayHitTest> =
+            Arc::new(elktracer_core::Group::new(objects?));
+        self.groups.insert(id.to_string(), group.clone());
+        Ok(group)
+    }
+
+    /// Creates the objects of a group, intersecting the spheres that are
+    /// neither rotated nor scaled together as a SIMD sphere set. Groups
+    /// are not picked by object, so their objects may be reordered.
+    fn create_group_objects(
+        &mut self,
+        group_objects: &[ObjectModel],
+    ) -> error::Result<Vec<Box<dyn RayHitTest>>> {
+        let mut spheres = Vec::new();
+        let mut objects = Vec::new();
+        for object in group_objects {
+            match &object.object_type {
+                ObjectType::Sphere { radius } if !object.is_transformed() => {
+                    spheres.push(self.sphere(object, *radius)?)
+                }
+                _ => objects.push(self.create_object(object)?),
+            }
+        }
+        if !spheres.is_empty() {
+            objects.push(Box::new(elktracer_core::SphereSet::new(spheres)));
+        }
+        Ok(objects)
+    }
+
+    fn warn_unsupported_motion(&self, scene_object: &ObjectModel) {
+        if scene_object.motion.is_some() {
+            log::warn!(
+                "Ignoring the motion of object {}, motion is only supported by spheres without rotation and scale",
+                scene_object.id
+            );
+        }
+    }
+}
+
+/// Creates the core material for `id`, first creating all materials it
+/// references. Already created materials are shared through the cache.
+fn resolve_material(
+    id: &str,
+    materials: &[MaterialModel],
+    core_materials: &mut HashMap<String, Arc<dyn Material>>,
+    resolving: &mut Vec<String>,
+) -> error::Result<Arc<dyn Material>> {
+    if let Some(material) = core_materials.get(id) {
+        return Ok(material.clone());
+    }
+    if resolving.iter().any(|resolving_id| resolving_id == id) {
+        return Err(Error::MaterialReferenceCycle(format!(
+            "{} -> {}",
+            resolving.join(" -> "),
+            id
+        )));
+    }
+
+    let material_model = materials
+        .iter()
+        .find(|material| material.id == id)
+        .ok_or_else(|| Error::MaterialNotFound(id.to_string()))?;
+    resolving.push(id.to_string());
+
+    let material: Arc<dyn Material> = match material_model.material_type.clone()
+    {
+        crate::model::MaterialType::Lambert { albedo } => {
+            Arc::new(elktracer_core::LambertMaterial::new(Color::from(albedo)))
+        }
+        crate::model::MaterialType::Metal { albedo, fuzziness } => Arc::new(
+            elktracer_core::MetalMaterial::new(Color::from(albedo), fuzziness),
+        ),
+        crate::model::MaterialType::Transparent {
+            refraction_index,
+            absorption_color,
+            absorption_density,
+            roughness,
+            dispersion,
+        } => Arc::new(
+            elktracer_core::TransparentMaterial::new(refraction_index)
+                .with_absorption(
+                    Color::from(absorption_color),
+                    absorption_density,
+                )
+                .with_roughness(roughness)
+                .with_dispersion(dispersion.map(Dispersion::from)),
+        ),
+        crate::model::MaterialType::Conductor { eta, k, roughness } => {
+            Arc::new(elktracer_core::ConductorMaterial::new(
+                Color::from(eta),
+                Color::from(k),
+                roughness,
+            ))
+        }
+        crate::model::MaterialType::Principled {
+            base_color,
+            metallic,
+            roughness,
+            specular,
+            clearcoat,
+            sheen,
+            transmission,
+        } => Arc::new(elktracer_core::PrincipledMaterial::new(
+            Color::from(base_color),
+            metallic,
+            roughness,
+            specular,
+            clearcoat,
+            sheen,
+            transmission,
+        )),
+        crate::model::MaterialType::Mix {
+            first,
+            second,
+            weight,
+        } => Arc::new(elktracer_core::MixMaterial::new(
+            resolve_material(&first, materials, core_materials, resolving)?,
+            resolve_material(&second, materials, core_materials, resolving)?,
+            create_texture(weight),
+        )),
+        crate::model::MaterialType::Coated {
+            base,
+            refraction_index,
+            roughness,
+            tint,
+        } => Arc::new(elktracer_core::CoatedMaterial::new(
+            resolve_material(&base, materials, core_materials, resolving)?,
+            refraction_index,
+            roughness,
+            Color::from(tint),
+        )),
+    };
+
+    resolving.pop();
+    core_materials.insert(id.to_string(), material.clone());
+    Ok(material)
+}
+
+fn create_texture(texture: TextureModel) -> Arc<dyn Texture> {
+    match texture {
+        TextureModel::Constant(value) => {
+            Arc::new(elktracer_core::ConstantTexture::new(value))
+        }
+        TextureModel::Procedural(ProceduralTextureModel::Checker {
+            scale,
+            even,
+            odd,
+        }) => Arc::new(elktracer_core::CheckerTexture::new(scale, even, odd)),
+    }
 }
 
-pub fn load_scene_model(file_path: &PathBuf) -> SceneModel {
-    let file = File::open(file_path).expect("Unable to open scene file");
+/// Loads the scene, resolving the relative paths it contains against the
+/// directory of the scene file.
+pub fn load_scene_model(file_path: &PathBuf) -> error::Result<SceneModel> {
+    let scene_file_error = |error: &dyn std::fmt::Display| {
+        Error::SceneFile(format!("{}: {}", file_path.display(), error))
+    };
+    let file =
+        File::open(file_path).map_err(|error| scene_file_error(&error))?;
     let reader = BufReader::new(file);
-    serde_json::from_reader(reader).expect("Unable to parse scene json")
+    let mut scene: SceneModel = serde_json::from_reader(reader)
+        .map_err(|error| scene_file_error(&error))?;
+    if let ApertureModel::Mask { directory, .. } = &mut scene.camera.aperture {
+        *directory = file_path.parent().map(Path::to_path_buf);
+    }
+    Ok(scene)
+}
+
+/// Hash identifying the scene in render checkpoints.
+pub fn scene_model_hash(scene_model: &SceneModel) -> u64 {
+    let json_data = serde_json::to_vec(scene_model)
+        .expect("Failed to serialize scene model");
+    CheckpointSettings::hash(&json_data)
 }
 
 pub fn save_scene_model(file_path: &PathBuf, scene_model: &SceneModel) {
@@ -69,3 +352,144 @@ pub fn save_scene_model(file_path: &PathBuf, scene_model: &SceneModel) {
     file.write_all(json_data.as_bytes())
         .expect("Failed to write JSON to file");
 }
+
+/// Writes the statistics of a render as JSON.
+pub fn save_render_stats(
+    file_path: &Path,
+    stats: &RenderStats,
+) -> error::Result<()> {
+    let stats_file_error = |error: &dyn std::fmt::Display| {
+        Error::RenderStatsFile(format!("{}: {}", file_path.display(), error))
+    };
+    let json_data =
+        serde_json::to_string_pretty(&RenderStatsModel::from(stats))
+            .map_err(|error| stats_file_error(&error))?;
+    let mut file =
+        File::create(file_path).map_err(|error| stats_file_error(&error))?;
+    file.write_all(json_data.as_bytes())
+        .map_err(|error| stats_file_error(&error))
+}
+
+#[cfg(test)]
+mod tests {
+    use elktracer_core::Interval;
+    use serde_json::json;
+
+    use super::*;
+
+    fn scene(
+        groups: serde_json::Value,
+        objects: serde_json::Value,
+    ) -> SceneModel {
+        let mut scene = test_util::scene_json(objects);
+        scene["groups"] = groups;
+        serde_json::from_value(scene).unwrap()
+    }
+
+    fn sphere(id: &str, position: [f64; 3]) -> serde_json::Value {
+        test_util::sphere_json(id, position, "gray")
+    }
+
+    fn instance(
+        id: &str,
+        group: &str,
+        position: [f64; 3],
+    ) -> serde_json::Value {
+        json!({
+            "id": id,
+            "position": position,
+            "object": { "type": "Instance", "group": group }
+        })
+    }
+
+    fn error(scene: &SceneModel) -> Error {
+        match get_scene_objects(scene) {
+            Ok(_) => panic!("Scene objects created for an invalid scene"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn get_scene_objects_should_place_nested_group_instances() {
+        let scene = scene(
+            json!([
+                { "id": "ball", "objects": [sphere("sphere", [0.0; 3])] },
+                {
+                    "id": "pair",
+                    "objects": [
+                        instance("left", "ball", [-1.5, 0.0, 0.0]),
+                        instance("right", "ball", [1.5, 0.0, 0.0])
+                    ]
+                }
+            ]),
+            json!([
+                instance("low", "pair", [0.0, -3.0, 0.0]),
+                instance("high", "pair", [0.0, 3.0, 0.0])
+            ]),
+        );
+
+        let objects = get_scene_objects(&scene).unwrap();
+
+        assert_eq!(objects.len(), 2);
+        let ray = elktracer_core::Ray::new(
+            Vec3f::new(-10.0, 3.0, 0.0),
+            Vec3f::new(1.0, 0.0, 0.0),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        assert!(objects[0].does_hit(&ray, &ray_t).is_none());
+        let hit = objects[1].does_hit(&ray, &ray_t).unwrap();
+        assert!((hit.t() - 7.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn get_scene_objects_should_fail_for_missing_group() {
+        let scene =
+            scene(json!([]), json!([instance("table", "legs", [0.0; 3])]));
+
+        assert_eq!(error(&scene), Error::GroupNotFound("legs".to_string()));
+    }
+
+    #[test]
+    fn get_scene_objects_should_fail_for_group_cycle() {
+        let scene = scene(
+            json!([
+                { "id": "a", "objects": [instance("to-b", "b", [0.0; 3])] },
+                { "id": "b", "objects": [instance("to-a", "a", [0.0; 3])] }
+            ]),
+            json!([instance("start", "a", [0.0; 3])]),
+        );
+
+        assert_eq!(
+            error(&scene),
+            Error::GroupReferenceCycle("a -> b -> a".to_string())
+        );
+    }
+
+    #[test]
+    fn get_scene_objects_should_fail_for_zero_scale_and_missing_material() {
+        let mut flat = sphere("flat", [0.0; 3]);
+        flat["scale"] = json!([1, 0, 1]);
+        let mut unknown = sphere("unknown", [0.0; 3]);
+        unknown["material"] = json!("missing");
+
+        assert_eq!(
+            error(&scene(json!([]), json!([flat]))),
+            Error::ZeroObjectScale("flat".to_string())
+        );
+        assert_eq!(
+            error(&scene(json!([]), json!([unknown]))),
+            Error::MaterialNotFound("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn save_render_stats_should_fail_for_missing_directory() {
+        let path = std::env::temp_dir()
+            .join(format!("elktracer_missing_{}", std::process::id()))
+            .join("stats.json");
+
+        let result = save_render_stats(&path, &RenderStats::default());
+
+        assert!(matches!(result, Err(Error::RenderStatsFile(_))));
+    }
+}
@@ -1,4 +1,16 @@
-use elktracer_core::{Camera, Vec3f};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use elktracer_core::{
+    ApertureMask, ApertureShape, Camera, CsgOperation, Dispersion, Error,
+    Interpolation, Keyframe, Motion, PhysicalCamera, Projection, RenderStats,
+    Vec3f,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -6,6 +18,102 @@ pub struct SceneModel {
     pub camera: CameraModel,
     pub materials: Vec<MaterialModel>,
     pub objects: Vec<ObjectModel>,
+    /// Named groups of objects, placed in the scene by instance objects.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupModel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct AnimationModel {
+    pub tracks: Vec<AnimationTrackModel>,
+}
+
+/// Keyframes of a single property. Before the first and after the last
+/// keyframe the property holds the respective keyframe value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct AnimationTrackModel {
+    pub target: AnimationTargetModel,
+    pub keyframes: Vec<AnimationKeyframeModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AnimationTargetModel {
+    Camera {
+        property: CameraPropertyModel,
+    },
+    Object {
+        id: String,
+        property: ObjectPropertyModel,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CameraPropertyModel {
+    Position,
+    LookAt,
+    Up,
+    FovVerticalDegrees,
+    DefocusAngle,
+    FocusDistance,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ObjectPropertyModel {
+    Position,
+    Radius,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AnimationKeyframeModel {
+    pub frame: f64,
+    pub value: AnimationValueModel,
+    /// Easing towards the next keyframe.
+    #[serde(default)]
+    pub interpolation: InterpolationModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum AnimationValueModel {
+    Scalar(f64),
+    Vector([f64; 3]),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(tag = "type")]
+pub enum InterpolationModel {
+    #[default]
+    Linear,
+    /// Cubic bezier timing curve with the control points (x1, y1) and
+    /// (x2, y2). Defaults to ease-in-out.
+    #[serde(rename_all = "kebab-case")]
+    Bezier {
+        #[serde(default = "default_bezier_control_points")]
+        control_points: [f64; 4],
+    },
+}
+
+fn default_bezier_control_points() -> [f64; 4] {
+    [0.42, 0.0, 0.58, 1.0]
+}
+
+impl From<InterpolationModel> for Interpolation {
+    fn from(value: InterpolationModel) -> Self {
+        match value {
+            InterpolationModel::Linear => Interpolation::Linear,
+            InterpolationModel::Bezier {
+                control_points: [x1, y1, x2, y2],
+            } => Interpolation::Bezier { x1, y1, x2, y2 },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -15,20 +123,217 @@ pub struct CameraModel {
     pub look_at: [f64; 3],
     pub up: [f64; 3],
 
-    pub fov_vertical_degrees: f64,
+    /// Required unless `physical` is set, which derives it from the lens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fov_vertical_degrees: Option<f64>,
+    /// Ignored if `physical` is set.
+    #[serde(default)]
     pub defocus_angle: f64,
     pub focus_distance: f64,
+
+    #[serde(default)]
+    pub projection: ProjectionModel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physical: Option<PhysicalCameraModel>,
+
+    #[serde(default)]
+    pub aperture: ApertureModel,
+    #[serde(default)]
+    pub cat_eye: f64,
+    #[serde(default)]
+    pub chromatic_aberration: f64,
+
+    #[serde(default)]
+    pub shutter_open: f64,
+    /// Ignored if `physical` is set, which derives it from the shutter time.
+    #[serde(default)]
+    pub shutter_close: f64,
+}
+
+impl TryFrom<CameraModel> for Camera {
+    type Error = Error;
+
+    fn try_from(value: CameraModel) -> Result<Self, Self::Error> {
+        let physical = value.physical.map(PhysicalCamera::from);
+        if let Some(physical) = &physical {
+            physical.validate()?;
+        }
+        let fov_vertical_degrees = match (value.fov_vertical_degrees, physical)
+        {
+            (_, Some(physical)) => physical.fov_vertical_degrees(),
+            (Some(fov_vertical_degrees), None) => fov_vertical_degrees,
+            (None, None) => {
+                return Err(Error::SceneFile(
+                    "The camera needs fov-vertical-degrees unless it is physical"
+                        .to_string(),
+                ));
+            }
+        };
+        let camera = Camera::new(
+            Vec3f::from(value.position),
+            Vec3f::from(value.look_at),
+            Vec3f::from(value.up),
+            fov_vertical_degrees,
+            value.defocus_angle,
+            value.focus_distance,
+        )
+        .with_projection(Projection::from(value.projection))
+        .with_aperture(ApertureShape::try_from(value.aperture)?)
+        .with_cat_eye(value.cat_eye)
+        .with_chromatic_aberration(value.chromatic_aberration)
+        .with_shutter(value.shutter_open, value.shutter_close);
+
+        Ok(match physical {
+            Some(physical) => camera.with_physical(&physical),
+            None => camera,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(tag = "type")]
+pub enum ApertureModel {
+    #[default]
+    Disk,
+    #[serde(rename_all = "kebab-case")]
+    Polygon { blades: u32, rotation_degrees: f64 },
+    /// Grayscale image of the aperture, brighter pixels let more light
+    /// through.
+    #[serde(rename_all = "kebab-case")]
+    Mask {
+        file: String,
+        /// Directory a relative `file` is resolved against, set to the
+        /// directory of the scene file by `load_scene_model`. Relative to
+        /// the working directory if not set.
+        #[serde(skip)]
+        directory: Option<PathBuf>,
+    },
+}
+
+impl TryFrom<ApertureModel> for ApertureShape {
+    type Error = Error;
+
+    fn try_from(value: ApertureModel) -> Result<Self, Self::Error> {
+        Ok(match value {
+            ApertureModel::Disk => ApertureShape::Disk,
+            ApertureModel::Polygon {
+                blades,
+                rotation_degrees,
+            } => ApertureShape::Polygon {
+                blades,
+                rotation_degrees,
+            },
+            ApertureModel::Mask { file, directory } => {
+                let path = match directory {
+                    Some(directory) => directory.join(file),
+                    None => PathBuf::from(file),
+                };
+                ApertureShape::Mask(load_aperture_mask(&path)?)
+            }
+        })
+    }
+}
+
+/// Loaded aperture masks by path and modification time of the image.
+type ApertureMasks = HashMap<PathBuf, (SystemTime, Arc<ApertureMask>)>;
+
+static APERTURE_MASKS: LazyLock<Mutex<ApertureMasks>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Reads the mask image only once for all renders and animation frames of
+/// a scene, and again after the image was changed.
+fn load_aperture_mask(path: &Path) -> Result<Arc<ApertureMask>, Error> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|error| {
+            Error::ApertureMask(format!("{}: {}", path.display(), error))
+        })?;
+
+    let mut masks = APERTURE_MASKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((loaded, mask)) = masks.get(path)
+        && *loaded == modified
+    {
+        return Ok(mask.clone());
+    }
+
+    let mask = Arc::new(ApertureMask::open(path)?);
+    masks.insert(path.to_path_buf(), (modified, mask.clone()));
+    Ok(mask)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PhysicalCameraModel {
+    pub focal_length_mm: f64,
+    pub sensor_width_mm: f64,
+    pub sensor_height_mm: f64,
+    pub f_number: f64,
+    pub shutter_seconds: f64,
+    pub iso: f64,
+}
+
+impl Default for PhysicalCameraModel {
+    fn default() -> Self {
+        PhysicalCameraModel::from(PhysicalCamera::default())
+    }
 }
 
-impl From<CameraModel> for Camera {
-    fn from(value: CameraModel) -> Self {
-        Camera {
-            position: Vec3f::from(value.position),
-            look_at: Vec3f::from(value.look_at),
-            up: Vec3f::from(value.up),
-            fov_vertical_degrees: value.fov_vertical_degrees,
-            defocus_angle: value.defocus_angle,
-            focus_distance: value.focus_distance,
+impl From<PhysicalCameraModel> for PhysicalCamera {
+    fn from(value: PhysicalCameraModel) -> Self {
+        PhysicalCamera::new(
+            value.focal_length_mm,
+            value.sensor_width_mm,
+            value.sensor_height_mm,
+            value.f_number,
+            value.shutter_seconds,
+            value.iso,
+        )
+    }
+}
+
+impl From<PhysicalCamera> for PhysicalCameraModel {
+    fn from(value: PhysicalCamera) -> Self {
+        Self {
+            focal_length_mm: value.focal_length_mm,
+            sensor_width_mm: value.sensor_width_mm,
+            sensor_height_mm: value.sensor_height_mm,
+            f_number: value.f_number,
+            shutter_seconds: value.shutter_seconds,
+            iso: value.iso,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectionModel {
+    #[default]
+    Perspective,
+    Orthographic,
+    Fisheye,
+    Equirectangular,
+}
+
+impl From<ProjectionModel> for Projection {
+    fn from(value: ProjectionModel) -> Self {
+        match value {
+            ProjectionModel::Perspective => Projection::Perspective,
+            ProjectionModel::Orthographic => Projection::Orthographic,
+            ProjectionModel::Fisheye => Projection::Fisheye,
+            ProjectionModel::Equirectangular => Projection::Equirectangular,
+        }
+    }
+}
+
+impl From<Projection> for ProjectionModel {
+    fn from(value: Projection) -> Self {
+        match value {
+            Projection::Perspective => ProjectionModel::Perspective,
+            Projection::Orthographic => ProjectionModel::Orthographic,
+            Projection::Fisheye => ProjectionModel::Fisheye,
+            Projection::Equirectangular => ProjectionModel::Equirectangular,
         }
     }
 }
@@ -36,7 +341,49 @@ impl From<CameraModel> for Camera {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum ObjectType {
-    Sphere { radius: f64 },
+    Sphere {
+        radius: f64,
+    },
+    /// Places the objects of the group with the object's transform. All
+    /// instances of a group share its geometry.
+    Instance {
+        group: String,
+    },
+    /// Combines two closed objects, placed relative to the object's
+    /// position, rotation and scale.
+    Csg {
+        operation: CsgOperationModel,
+        first: Box<ObjectModel>,
+        second: Box<ObjectModel>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CsgOperationModel {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl From<CsgOperationModel> for CsgOperation {
+    fn from(value: CsgOperationModel) -> Self {
+        match value {
+            CsgOperationModel::Union => CsgOperation::Union,
+            CsgOperationModel::Intersection => CsgOperation::Intersection,
+            CsgOperationModel::Difference => CsgOperation::Difference,
+        }
+    }
+}
+
+impl From<CsgOperation> for CsgOperationModel {
+    fn from(value: CsgOperation) -> Self {
+        match value {
+            CsgOperation::Union => CsgOperationModel::Union,
+            CsgOperation::Intersection => CsgOperationModel::Intersection,
+            CsgOperation::Difference => CsgOperationModel::Difference,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -44,10 +391,37 @@ pub enum ObjectType {
 pub struct ObjectModel {
     pub id: String,
     pub position: [f64; 3],
-    #[serde(rename = "material")]
+    /// Rotation around the x, y and z axes in degrees, applied after the
+    /// scale.
+    #[serde(default, skip_serializing_if = "is_zero_rotation")]
+    pub rotation: [f64; 3],
+    #[serde(default = "unit_scale", skip_serializing_if = "is_unit_scale")]
+    pub scale: [f64; 3],
+    /// Unused by instances and CSG, whose objects have their own
+    /// materials.
+    #[serde(
+        rename = "material",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
     pub material_id: String,
     #[serde(rename = "object")]
     pub object_type: ObjectType,
+    /// Only supported by spheres without rotation and scale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<MotionModel>,
+}
+
+fn unit_scale() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn is_unit_scale(scale: &[f64; 3]) -> bool {
+    *scale == unit_scale()
+}
+
+fn is_zero_rotation(rotation: &[f64; 3]) -> bool {
+    *rotation == [0.0, 0.0, 0.0]
 }
 
 impl ObjectModel {
@@ -60,8 +434,65 @@ impl ObjectModel {
         Self {
             id: id.to_string(),
             position,
+            rotation: [0.0, 0.0, 0.0],
+            scale: unit_scale(),
             material_id: material_id.to_string(),
             object_type,
+            motion: None,
+        }
+    }
+
+    /// Whether the object is rotated or scaled, in which case it is placed
+    /// by an instance.
+    pub fn is_transformed(&self) -> bool {
+        !is_zero_rotation(&self.rotation) || !is_unit_scale(&self.scale)
+    }
+}
+
+/// Objects that are placed together by instance objects referencing the
+/// group's id. Groups may contain instances of other groups.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct GroupModel {
+    pub id: String,
+    pub objects: Vec<ObjectModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum MotionModel {
+    /// Velocity in units per second.
+    Linear { velocity: [f64; 3] },
+    /// Positions at given times in seconds, replacing the object position.
+    Keyframes { keyframes: Vec<KeyframeModel> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct KeyframeModel {
+    pub time: f64,
+    pub position: [f64; 3],
+}
+
+impl From<MotionModel> for Motion {
+    fn from(value: MotionModel) -> Self {
+        match value {
+            MotionModel::Linear { velocity } => Motion::Linear {
+                velocity: Vec3f::from(velocity),
+            },
+            MotionModel::Keyframes { mut keyframes } => {
+                keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+                Motion::Keyframes(
+                    keyframes
+                        .into_iter()
+                        .map(|keyframe| {
+                            Keyframe::new(
+                                keyframe.time,
+                                Vec3f::from(keyframe.position),
+                            )
+                        })
+                        .collect(),
+                )
+            }
         }
     }
 }
@@ -79,9 +510,99 @@ pub enum MaterialType {
     #[serde(rename_all = "kebab-case")]
     Transparent {
         refraction_index: f64,
+        #[serde(default = "default_white")]
+        absorption_color: [f32; 3],
+        #[serde(default)]
+        absorption_density: f64,
+        #[serde(default)]
+        roughness: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dispersion: Option<DispersionModel>,
+    },
+    /// Microfacet metal described by its complex refraction index per color
+    /// channel. `Metal` is kept as the legacy fuzzy reflection model.
+    #[serde(rename_all = "kebab-case")]
+    Conductor {
+        eta: [f64; 3],
+        k: [f64; 3],
+        roughness: f64,
+    },
+    #[serde(rename_all = "kebab-case")]
+    Principled {
+        base_color: [f32; 3],
+        metallic: f64,
+        roughness: f64,
+        specular: f64,
+        clearcoat: f64,
+        sheen: f64,
+        transmission: f64,
+    },
+    /// Blends the materials with the IDs `first` and `second`. A weight of
+    /// 0 yields only `first`, a weight of 1 only `second`.
+    Mix {
+        first: String,
+        second: String,
+        weight: TextureModel,
+    },
+    /// Dielectric coat over the material with the ID `base`.
+    #[serde(rename_all = "kebab-case")]
+    Coated {
+        base: String,
+        refraction_index: f64,
+        roughness: f64,
+        #[serde(default = "default_white")]
+        tint: [f32; 3],
     },
 }
 
+/// Scalar texture, either a plain number or a procedural pattern.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum TextureModel {
+    Constant(f64),
+    Procedural(ProceduralTextureModel),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type")]
+pub enum ProceduralTextureModel {
+    Checker { scale: f64, even: f64, odd: f64 },
+}
+
+/// Wavelength dependent refraction index, used in spectral rendering only.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type")]
+pub enum DispersionModel {
+    Cauchy { a: f64, b: f64 },
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl From<DispersionModel> for Dispersion {
+    fn from(value: DispersionModel) -> Self {
+        match value {
+            DispersionModel::Cauchy { a, b } => Dispersion::Cauchy { a, b },
+            DispersionModel::Sellmeier { b, c } => {
+                Dispersion::Sellmeier { b, c }
+            }
+        }
+    }
+}
+
+impl From<Dispersion> for DispersionModel {
+    fn from(value: Dispersion) -> Self {
+        match value {
+            Dispersion::Cauchy { a, b } => DispersionModel::Cauchy { a, b },
+            Dispersion::Sellmeier { b, c } => {
+                DispersionModel::Sellmeier { b, c }
+            }
+        }
+    }
+}
+
+fn default_white() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MaterialModel {
     pub id: String,
@@ -97,3 +618,115 @@ impl MaterialModel {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenderStatsModel {
+    pub wall_time_seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_time_seconds: Option<f64>,
+    pub primary_rays: u64,
+    pub secondary_rays: u64,
+    pub rays_per_second: f64,
+    pub object_tests: u64,
+    pub object_tests_per_ray: f64,
+    pub path_length_histogram: Vec<u64>,
+    pub invalid_samples: u64,
+}
+
+impl From<&RenderStats> for RenderStatsModel {
+    fn from(value: &RenderStats) -> Self {
+        Self {
+            wall_time_seconds: value.wall_time.as_secs_f64(),
+            cpu_time_seconds: value
+                .cpu_time
+                .map(|cpu_time| cpu_time.as_secs_f64()),
+            primary_rays: value.primary_rays,
+            secondary_rays: value.secondary_rays,
+            rays_per_second: value.rays_per_second(),
+            object_tests: value.object_tests,
+            object_tests_per_ray: value.object_tests_per_ray(),
+            path_length_histogram: value.path_length_histogram.clone(),
+            invalid_samples: value.invalid_samples,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elktracer_core::image_rs::{GrayImage, Luma};
+
+    use super::*;
+
+    #[test]
+    fn aperture_mask_should_load_once_relative_to_directory() {
+        let directory = std::env::temp_dir()
+            .join(format!("elktracer_aperture_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        GrayImage::from_pixel(2, 2, Luma([255]))
+            .save(directory.join("mask.png"))
+            .unwrap();
+        let model = ApertureModel::Mask {
+            file: "mask.png".to_string(),
+            directory: Some(directory.clone()),
+        };
+
+        let first = ApertureShape::try_from(model.clone());
+        let second = ApertureShape::try_from(model);
+        let missing = ApertureShape::try_from(ApertureModel::Mask {
+            file: "mask.png".to_string(),
+            directory: None,
+        });
+        fs::remove_dir_all(directory).unwrap();
+
+        let (Ok(ApertureShape::Mask(first)), Ok(ApertureShape::Mask(second))) =
+            (first, second)
+        else {
+            panic!("Aperture mask not loaded");
+        };
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(matches!(missing, Err(Error::ApertureMask(_))));
+    }
+
+    #[test]
+    fn camera_should_need_fov_unless_physical_and_a_valid_lens() {
+        let camera = |camera: serde_json::Value| {
+            let mut scene = crate::test_util::scene_json(serde_json::json!([]));
+            scene["camera"]
+                .as_object_mut()
+                .unwrap()
+                .extend(camera.as_object().unwrap().clone());
+            let scene: SceneModel = serde_json::from_value(scene).unwrap();
+            Camera::try_from(scene.camera)
+        };
+        let lens = |focal_length_mm: f64, f_number: f64| {
+            serde_json::to_value(PhysicalCameraModel {
+                focal_length_mm,
+                f_number,
+                ..PhysicalCameraModel::default()
+            })
+            .unwrap()
+        };
+
+        let missing_fov = camera(serde_json::json!({
+            "fov-vertical-degrees": null
+        }));
+        assert!(matches!(missing_fov, Err(Error::SceneFile(_))));
+
+        let physical = camera(serde_json::json!({
+            "fov-vertical-degrees": null,
+            "physical": lens(50.0, 16.0)
+        }))
+        .unwrap();
+        assert!((physical.fov_vertical_degrees - 26.99).abs() < 0.01);
+
+        assert_eq!(
+            camera(serde_json::json!({ "physical": lens(0.0, 16.0) })).err(),
+            Some(Error::InvalidFocalLength(0.0))
+        );
+        assert_eq!(
+            camera(serde_json::json!({ "physical": lens(50.0, -1.0) })).err(),
+            Some(Error::InvalidFNumber(-1.0))
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use serde_json::{Value, json};
+
+/// Scene with the camera at z = 10 looking at the origin and a gray Lambert
+/// material with the id "gray" for the objects.
+pub fn scene_json(objects: Value) -> Value {
+    json!({
+        "camera": {
+            "position": [0, 0, 10],
+            "look-at": [0, 0, 0],
+            "up": [0, 1, 0],
+            "fov-vertical-degrees": 20,
+            "focus-distance": 10
+        },
+        "materials": [{
+            "id": "gray",
+            "material": { "type": "Lambert", "albedo": [0.5, 0.5, 0.5] }
+        }],
+        "objects": objects
+    })
+}
+
+/// Sphere object with radius one.
+pub fn sphere_json(id: &str, position: [f64; 3], material: &str) -> Value {
+    json!({
+        "id": id,
+        "position": position,
+        "material": material,
+        "object": { "type": "Sphere", "radius": 1 }
+    })
+}
@@ -0,0 +1,362 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use elktracer_core::{
+    Camera, Image, RayHitTest, RenderOptions, RenderProgress, RenderStats,
+};
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    Rendering,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Rendering => "rendering",
+            JobState::Completed => "completed",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobState::Completed | JobState::Failed | JobState::Cancelled
+        )
+    }
+}
+
+impl fmt::Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Snapshot of a job for status responses.
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    pub id: JobId,
+    pub state: JobState,
+    pub completed_samples: u16,
+    pub samples_per_pixel: u16,
+    pub progress: f64,
+    pub error: Option<String>,
+    pub stats: Option<RenderStats>,
+}
+
+struct Job {
+    camera: Camera,
+    /// Objects of the scene, moved to the worker when rendering starts.
+    objects: Vec<Box<dyn RayHitTest>>,
+    options: RenderOptions,
+    state: JobState,
+    progress: RenderProgress,
+    image: Option<Image>,
+    stats: Option<RenderStats>,
+    error: Option<String>,
+}
+
+impl Job {
+    fn status(&self, id: JobId) -> JobStatus {
+        let samples_per_pixel = self.options.samples_per_pixel;
+        let completed_samples = match self.state {
+            JobState::Completed => samples_per_pixel,
+            _ => self.progress.completed_samples(),
+        };
+        JobStatus {
+            id,
+            state: self.state,
+            completed_samples,
+            samples_per_pixel,
+            progress: completed_samples as f64 / samples_per_pixel as f64,
+            error: self.error.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: JobId,
+    queue: VecDeque<JobId>,
+    /// Finished jobs in the order they finished, oldest first.
+    finished: VecDeque<JobId>,
+    jobs: BTreeMap<JobId, Job>,
+}
+
+impl Jobs {
+    /// Keeps the job for status and image requests until it is deleted or
+    /// more than `max_finished_jobs` newer jobs have finished.
+    fn finish(&mut self, id: JobId, max_finished_jobs: usize) {
+        self.finished.push_back(id);
+        while self.finished.len() > max_finished_jobs {
+            if let Some(evicted_id) = self.finished.pop_front() {
+                self.jobs.remove(&evicted_id);
+                log::info!("Evicted finished job {}", evicted_id);
+            }
+        }
+    }
+}
+
+struct Shared {
+    jobs: Mutex<Jobs>,
+    job_queued: Condvar,
+    preview_interval: Duration,
+    max_finished_jobs: usize,
+}
+
+/// Render jobs in submission order, rendered one at a time by a worker
+/// thread. Clones share the same jobs.
+#[derive(Clone)]
+pub struct JobQueue {
+    shared: Arc<Shared>,
+}
+
+impl JobQueue {
+    /// Rendering jobs resolve a preview image at most once per interval.
+    /// Only the last `max_finished_jobs` finished jobs and their images are
+    /// kept.
+    pub fn new(preview_interval: Duration, max_finished_jobs: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                jobs: Mutex::new(Jobs {
+                    next_id: 1,
+                    ..Jobs::default()
+                }),
+                job_queued: Condvar::new(),
+                preview_interval,
+                max_finished_jobs,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Jobs> {
+        self.shared.jobs.lock().expect("Job queue lock poisoned")
+    }
+
+    /// Starts the thread rendering the queued jobs, which runs as long as
+    /// the process.
+    pub fn start_worker(&self) -> JoinHandle<()> {
+        let queue = self.clone();
+        thread::Builder::new()
+            .name("render-worker".to_string())
+            .spawn(move || {
+                loop {
+                    queue.render_next();
+                }
+            })
+            .expect("Unable to start render worker thread")
+    }
+
+    /// Queues a job whose options and camera were validated.
+    pub fn submit(
+        &self,
+        camera: Camera,
+        objects: Vec<Box<dyn RayHitTest>>,
+        options: RenderOptions,
+    ) -> JobId {
+        let mut jobs = self.lock();
+        let id = jobs.next_id;
+        jobs.next_id += 1;
+        jobs.jobs.insert(
+            id,
+            Job {
+                camera,
+                objects,
+                options,
+                state: JobState::Queued,
+                progress: RenderProgress::new()
+                    .with_preview_interval(self.shared.preview_interval),
+                image: None,
+                stats: None,
+                error: None,
+            },
+        );
+        jobs.queue.push_back(id);
+        self.shared.job_queued.notify_one();
+        log::info!("Queued job {}", id);
+        id
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        self.lock().jobs.get(&id).map(|job| job.status(id))
+    }
+
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.lock()
+            .jobs
+            .iter()
+            .map(|(id, job)| job.status(*id))
+            .collect()
+    }
+
+    /// Final image of a completed job, or the latest preview of a rendering
+    /// or cancelled one.
+    pub fn image(&self, id: JobId) -> Option<Image> {
+        let jobs = self.lock();
+        let job = jobs.jobs.get(&id)?;
+        job.image.clone().or_else(|| job.progress.preview())
+    }
+
+    /// Removes a queued job from the queue, stops a rendering one before
+    /// its next row and removes a finished one with its image. Returns the
+    /// status after cancelling, the last status of removed jobs or `None`
+    /// for unknown jobs.
+    pub fn delete(&self, id: JobId) -> Option<JobStatus> {
+        let mut jobs = self.lock();
+        let state = jobs.jobs.get(&id)?.state;
+        if state.is_finished() {
+            jobs.finished.retain(|finished_id| *finished_id != id);
+            let job = jobs.jobs.remove(&id)?;
+            log::info!("Removed job {}", id);
+            return Some(job.status(id));
+        }
+        match state {
+            JobState::Queued => {
+                jobs.queue.retain(|queued_id| *queued_id != id);
+                let job = jobs.jobs.get_mut(&id)?;
+                job.state = JobState::Cancelled;
+                job.objects = Vec::new();
+                jobs.finish(id, self.shared.max_finished_jobs);
+                log::info!("Cancelled queued job {}", id);
+            }
+            JobState::Rendering => {
+                jobs.jobs.get(&id)?.progress.cancel();
+                log::info!("Cancelling job {}", id);
+            }
+            _ => {}
+        }
+        jobs.jobs.get(&id).map(|job| job.status(id))
+    }
+
+    /// Waits for the next queued job and renders it.
+    fn render_next(&self) {
+        let (id, camera, objects, options, progress) = {
+            let mut jobs = self.lock();
+            let id = loop {
+                match jobs.queue.pop_front() {
+                    Some(id) => break id,
+                    None => {
+                        jobs = self
+                            .shared
+                            .job_queued
+                            .wait(jobs)
+                            .expect("Job queue lock poisoned")
+                    }
+                }
+            };
+            let job = jobs.jobs.get_mut(&id).expect("Queued job is missing");
+            job.state = JobState::Rendering;
+            (
+                id,
+                job.camera.clone(),
+                std::mem::take(&mut job.objects),
+                job.options,
+                job.progress.clone(),
+            )
+        };
+
+        log::info!("Rendering job {}", id);
+        // A panicking render fails its job instead of the worker, which
+        // keeps rendering the queued jobs.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            elktracer_core::Raytracer::new()
+                .with_progress(progress)
+                .try_render_image(&camera, objects, &options)
+        }));
+
+        let mut jobs = self.lock();
+        let job = jobs.jobs.get_mut(&id).expect("Rendered job is missing");
+        let result = match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown error".to_string());
+                job.state = JobState::Failed;
+                job.error = Some(format!("Render panicked: {}", message));
+                log::error!("Job {} panicked: {}", id, message);
+                jobs.finish(id, self.shared.max_finished_jobs);
+                return;
+            }
+        };
+        match result {
+            Ok(rendered_image) => {
+                job.state = JobState::Completed;
+                job.image = Some(rendered_image.image);
+                job.stats = Some(rendered_image.stats);
+                log::info!("Completed job {}", id);
+            }
+            Err(elktracer_core::Error::RenderCancelled) => {
+                job.state = JobState::Cancelled;
+                log::info!("Cancelled job {}", id);
+            }
+            Err(error) => {
+                job.state = JobState::Failed;
+                job.error = Some(error.to_string());
+                log::error!("Job {} failed: {}", id, error);
+            }
+        }
+        jobs.finish(id, self.shared.max_finished_jobs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use elktracer_core::{Color, Material, Ray, Sphere, Vec3f};
+
+    use super::*;
+
+    struct PanickingMaterial;
+
+    impl Material for PanickingMaterial {
+        fn scatter(
+            &self,
+            _ray: &Ray,
+            _hit_point: Vec3f,
+            _hit_normal: Vec3f,
+            _is_hit_front_face: bool,
+        ) -> Option<(Ray, Color)> {
+            panic!("broken material");
+        }
+    }
+
+    #[test]
+    fn render_next_should_fail_panicking_jobs_and_keep_rendering() {
+        let queue = JobQueue::new(Duration::ZERO, 10);
+        let options = RenderOptions::new(4, 1.0, 1, 1);
+        // Encloses the camera, so that every camera ray hits it.
+        let sphere =
+            Sphere::new(Vec3f::zero(), 1000.0, Arc::new(PanickingMaterial));
+        let broken_id =
+            queue.submit(Camera::default(), vec![Box::new(sphere)], options);
+        let id = queue.submit(Camera::default(), Vec::new(), options);
+
+        queue.render_next();
+        queue.render_next();
+
+        let broken = queue.status(broken_id).unwrap();
+        assert_eq!(broken.state, JobState::Failed);
+        assert!(broken.error.unwrap().contains("broken material"));
+        assert_eq!(queue.status(id).unwrap().state, JobState::Completed);
+    }
+}
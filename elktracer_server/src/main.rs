@@ -0,0 +1,435 @@
+mod jobs;
+mod model;
+
+use std::{
+    io::{Cursor, Read},
+    time::Duration,
+};
+
+use clap::Parser;
+use elktracer_core::{Camera, RenderOptions, image_rs};
+use elktracer_json::get_scene_objects;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use self::jobs::{JobId, JobQueue};
+use self::model::{
+    ErrorModel, JobCreatedModel, JobRequestModel, JobStatusModel,
+};
+
+/// Renders scenes submitted over HTTP one at a time.
+///
+/// POST /jobs queues a job with a scene and render options, GET /jobs and
+/// GET /jobs/ID report the progress, GET /jobs/ID/image returns the PNG
+/// preview or final image and DELETE /jobs/ID cancels the job or removes
+/// a finished one.
+#[derive(Parser)]
+#[command(version, about, long_about)]
+struct Args {
+    /// Address to listen on, only local by default
+    #[arg(
+        long,
+        short = 'a',
+        value_name = "ADDRESS",
+        default_value = "127.0.0.1:8080"
+    )]
+    address: String,
+    /// Seconds between preview images of the rendering job
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "2",
+        value_parser = parse_seconds
+    )]
+    preview_interval: Duration,
+    /// Number of finished jobs whose status and image are kept
+    #[arg(long, value_name = "COUNT", default_value_t = 100)]
+    max_finished_jobs: usize,
+}
+
+/// Parses a non-negative, finite number of seconds.
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds = value.parse::<f64>().map_err(|error| error.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|error| error.to_string())
+}
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+/// Largest accepted job submission in bytes.
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+
+fn main() {
+    elktracer_core::logging::initialize();
+
+    let args = Args::parse();
+
+    let server = match Server::http(&args.address) {
+        Ok(server) => server,
+        Err(error) => {
+            log::error!("Unable to listen on {}: {}", args.address, error);
+            std::process::exit(1);
+        }
+    };
+    log::info!("Listening on http://{}", server.server_addr());
+
+    let queue = JobQueue::new(args.preview_interval, args.max_finished_jobs);
+    queue.start_worker();
+
+    serve(&server, &queue);
+}
+
+/// Answers the requests until the server is closed.
+fn serve(server: &Server, queue: &JobQueue) {
+    for mut request in server.incoming_requests() {
+        log::debug!("{} {}", request.method(), request.url());
+        let response = handle_request(queue, &mut request);
+        if let Err(error) = request.respond(response) {
+            log::warn!("Unable to send response: {}", error);
+        }
+    }
+}
+
+fn handle_request(queue: &JobQueue, request: &mut Request) -> HttpResponse {
+    let url = request.url().to_string();
+    let segments = url
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Post, ["jobs"]) => submit_job(queue, request),
+        (Method::Get, ["jobs"]) => json_response(
+            200,
+            &queue
+                .statuses()
+                .iter()
+                .map(JobStatusModel::from)
+                .collect::<Vec<_>>(),
+        ),
+        (Method::Get, ["jobs", id]) => match job_id(id)
+            .and_then(|id| queue.status(id))
+        {
+            Some(status) => json_response(200, &JobStatusModel::from(&status)),
+            None => error_response(404, format!("Unknown job {}", id)),
+        },
+        (Method::Get, ["jobs", id, "image"]) => {
+            let Some(id) = job_id(id).filter(|id| queue.status(*id).is_some())
+            else {
+                return error_response(404, format!("Unknown job {}", id));
+            };
+            match queue.image(id) {
+                Some(image) => png_response(image),
+                None => {
+                    error_response(404, format!("Job {} has no image yet", id))
+                }
+            }
+        }
+        (Method::Delete, ["jobs", id]) => match job_id(id)
+            .and_then(|id| queue.delete(id))
+        {
+            Some(status) => json_response(200, &JobStatusModel::from(&status)),
+            None => error_response(404, format!("Unknown job {}", id)),
+        },
+        _ => error_response(404, format!("No route for {}", url)),
+    }
+}
+
+/// Queues the scene of the request body after validating its options and
+/// building the camera and objects, so that invalid jobs are refused
+/// instead of failing later.
+fn submit_job(queue: &JobQueue, request: &mut Request) -> HttpResponse {
+    let too_large = || {
+        error_response(
+            413,
+            format!("Job exceeds the limit of {} bytes", MAX_BODY_LENGTH),
+        )
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_BODY_LENGTH)
+    {
+        return too_large();
+    }
+    let mut body = String::new();
+    if let Err(error) = request
+        .as_reader()
+        .take(MAX_BODY_LENGTH as u64 + 1)
+        .read_to_string(&mut body)
+    {
+        return error_response(400, format!("Unable to read body: {}", error));
+    }
+    if body.len() > MAX_BODY_LENGTH {
+        return too_large();
+    }
+    let job_request: JobRequestModel = match serde_json::from_str(&body) {
+        Ok(job_request) => job_request,
+        Err(error) => {
+            return error_response(400, format!("Invalid job: {}", error));
+        }
+    };
+
+    let options = match RenderOptions::try_from(job_request.options) {
+        Ok(options) => options,
+        Err(error) => return error_response(400, error),
+    };
+    let scene = job_request.scene;
+    let built = options.validate().and_then(|_| {
+        let camera = Camera::try_from(scene.camera.clone())?;
+        camera.validate()?;
+        Ok((camera, get_scene_objects(&scene)?))
+    });
+    let (camera, objects) = match built {
+        Ok(built) => built,
+        Err(error) => return error_response(400, error.to_string()),
+    };
+
+    let id = queue.submit(camera, objects, options);
+    json_response(201, &JobCreatedModel { id })
+}
+
+fn job_id(segment: &str) -> Option<JobId> {
+    segment.parse().ok()
+}
+
+fn json_response<T: Serialize>(status_code: u16, body: &T) -> HttpResponse {
+    let json_data =
+        serde_json::to_vec(body).expect("Failed to serialize response");
+    Response::from_data(json_data)
+        .with_status_code(status_code)
+        .with_header(content_type("application/json"))
+}
+
+fn error_response(status_code: u16, error: String) -> HttpResponse {
+    json_response(status_code, &ErrorModel { error })
+}
+
+fn png_response(image: elktracer_core::Image) -> HttpResponse {
+    let mut png_data = Cursor::new(Vec::new());
+    let encoded = image_rs::DynamicImage::try_from(image)
+        .map_err(|error| error.to_string())
+        .and_then(|image| {
+            image
+                .write_to(&mut png_data, image_rs::ImageFormat::Png)
+                .map_err(|error| error.to_string())
+        });
+    match encoded {
+        Ok(()) => Response::from_data(png_data.into_inner())
+            .with_header(content_type("image/png")),
+        Err(error) => {
+            error_response(500, format!("Unable to encode image: {}", error))
+        }
+    }
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], value.as_bytes())
+        .expect("Invalid content type header")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        net::{SocketAddr, TcpStream},
+        time::{Duration, Instant},
+    };
+
+    use elktracer_json::test_util::{scene_json, sphere_json};
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    /// Serves a new queue on an ephemeral local port. Without the worker
+    /// thread, jobs stay queued.
+    fn start_server(
+        start_worker: bool,
+        max_finished_jobs: usize,
+    ) -> SocketAddr {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        let queue = JobQueue::new(Duration::ZERO, max_finished_jobs);
+        if start_worker {
+            queue.start_worker();
+        }
+        std::thread::spawn(move || serve(&server, &queue));
+        address
+    }
+
+    fn send(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+        body: &str,
+    ) -> (u16, Vec<u8>) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}\r\n{}",
+            method, path, headers, body
+        )
+        .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .unwrap();
+        let status_line = String::from_utf8_lossy(&response[..header_end]);
+        let status_code = status_line.split(' ').nth(1).unwrap().parse();
+        (status_code.unwrap(), response[header_end + 4..].to_vec())
+    }
+
+    fn request(address: SocketAddr, method: &str, path: &str) -> (u16, Value) {
+        let (status_code, body) = send(address, method, path, "", "");
+        (status_code, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn post_job(address: SocketAddr, job: &Value) -> (u16, Value) {
+        let body = job.to_string();
+        let (status_code, body) = send(
+            address,
+            "POST",
+            "/jobs",
+            &format!("Content-Length: {}\r\n", body.len()),
+            &body,
+        );
+        (status_code, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn job(material: &str) -> Value {
+        json!({
+            "scene": scene_json(json!([sphere_json("ball", [0.0; 3], material)])),
+            "options": {
+                "image-width": 8,
+                "aspect-ratio": 1.0,
+                "samples-per-pixel": 2,
+                "max-ray-depth": 2
+            }
+        })
+    }
+
+    #[test]
+    fn args_should_reject_invalid_preview_intervals() {
+        let parse = |preview_interval: &str| {
+            Args::try_parse_from([
+                "elktracer_server",
+                "--preview-interval",
+                preview_interval,
+            ])
+            .map(|args| args.preview_interval)
+        };
+
+        assert_eq!(parse("0.5").unwrap(), Duration::from_millis(500));
+        for preview_interval in ["-1", "inf", "NaN", "1e30", "soon"] {
+            assert!(parse(preview_interval).is_err(), "{}", preview_interval);
+        }
+    }
+
+    #[test]
+    fn post_jobs_should_queue_valid_scenes_and_refuse_invalid_ones() {
+        let address = start_server(false, 10);
+
+        let (status_code, created) = post_job(address, &job("gray"));
+        assert_eq!(status_code, 201);
+        assert_eq!(created["id"], 1);
+
+        let (status_code, error) = post_job(address, &job("missing"));
+        assert_eq!(status_code, 400);
+        assert!(error["error"].as_str().unwrap().contains("missing"));
+        let (status_code, _) = post_job(address, &json!({ "scene": {} }));
+        assert_eq!(status_code, 400);
+
+        let (status_code, status) = request(address, "GET", "/jobs/1");
+        assert_eq!(status_code, 200);
+        assert_eq!(status["state"], "queued");
+        assert_eq!(status["progress"], 0.0);
+        let (status_code, jobs) = request(address, "GET", "/jobs");
+        assert_eq!(status_code, 200);
+        assert_eq!(jobs.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn post_jobs_should_refuse_too_large_bodies() {
+        let address = start_server(false, 10);
+
+        let (status_code, _) = send(
+            address,
+            "POST",
+            "/jobs",
+            &format!("Content-Length: {}\r\n", MAX_BODY_LENGTH + 1),
+            "",
+        );
+
+        assert_eq!(status_code, 413);
+    }
+
+    #[test]
+    fn unknown_jobs_should_not_be_found() {
+        let address = start_server(false, 10);
+
+        for (method, path) in [
+            ("GET", "/jobs/7"),
+            ("GET", "/jobs/7/image"),
+            ("DELETE", "/jobs/7"),
+            ("GET", "/jobs/seven"),
+        ] {
+            let (status_code, _) = request(address, method, path);
+            assert_eq!(status_code, 404, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn get_image_should_return_png_of_completed_job() {
+        let address = start_server(true, 10);
+        post_job(address, &job("gray"));
+
+        let start = Instant::now();
+        let status = loop {
+            let (_, status) = request(address, "GET", "/jobs/1");
+            if status["state"] != "queued" && status["state"] != "rendering" {
+                break status;
+            }
+            assert!(start.elapsed() < Duration::from_secs(60));
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(status["state"], "completed");
+        assert_eq!(status["completed-samples"], 2);
+        assert_eq!(status["progress"], 1.0);
+
+        let (status_code, image) =
+            send(address, "GET", "/jobs/1/image", "", "");
+        assert_eq!(status_code, 200);
+        assert!(image.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn delete_should_cancel_queued_jobs_and_remove_finished_ones() {
+        let address = start_server(false, 1);
+        for _ in 0..3 {
+            post_job(address, &job("gray"));
+        }
+
+        // No image before the job is rendered.
+        let (status_code, _) = request(address, "GET", "/jobs/1/image");
+        assert_eq!(status_code, 404);
+
+        let (status_code, status) = request(address, "DELETE", "/jobs/1");
+        assert_eq!(status_code, 200);
+        assert_eq!(status["state"], "cancelled");
+        assert_eq!(request(address, "GET", "/jobs/1").0, 200);
+
+        let (status_code, status) = request(address, "DELETE", "/jobs/1");
+        assert_eq!(status_code, 200);
+        assert_eq!(status["state"], "cancelled");
+        assert_eq!(request(address, "GET", "/jobs/1").0, 404);
+
+        // Only the last finished job is kept.
+        request(address, "DELETE", "/jobs/2");
+        request(address, "DELETE", "/jobs/3");
+        assert_eq!(request(address, "GET", "/jobs/2").0, 404);
+        assert_eq!(request(address, "GET", "/jobs/3").0, 200);
+    }
+}
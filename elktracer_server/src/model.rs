@@ -0,0 +1,116 @@
+use std::str::FromStr;
+
+use elktracer_core::{FilterType, IntegratorType, RenderOptions, RenderRegion};
+use elktracer_json::model::{RenderStatsModel, SceneModel};
+use serde::{Deserialize, Serialize};
+
+use crate::jobs::{JobId, JobStatus};
+
+/// Body of a job submission.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct JobRequestModel {
+    pub scene: SceneModel,
+    pub options: RenderOptionsModel,
+}
+
+/// Render options of a job, with the defaults of the render command for
+/// everything but the image size, samples and depth.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenderOptionsModel {
+    pub image_width: u32,
+    pub aspect_ratio: f64,
+    pub samples_per_pixel: u16,
+    pub max_ray_depth: u16,
+    #[serde(default)]
+    pub russian_roulette_min_depth: Option<u16>,
+    /// Name of the integrator, e.g. `path-tracer`.
+    #[serde(default)]
+    pub integrator: Option<String>,
+    #[serde(default)]
+    pub spectral: bool,
+    /// Name of the reconstruction filter, e.g. `mitchell-netravali`.
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub filter_radius: Option<f64>,
+    /// Rectangle `[x, y, width, height]` to render.
+    #[serde(default)]
+    pub region: Option<[u32; 4]>,
+    #[serde(default)]
+    pub crop: bool,
+}
+
+impl TryFrom<RenderOptionsModel> for RenderOptions {
+    type Error = String;
+
+    fn try_from(value: RenderOptionsModel) -> Result<Self, Self::Error> {
+        let mut options = RenderOptions::new(
+            value.image_width,
+            value.aspect_ratio,
+            value.samples_per_pixel,
+            value.max_ray_depth,
+        );
+        if let Some(depth) = value.russian_roulette_min_depth {
+            options.russian_roulette_min_depth = depth;
+        }
+        if let Some(integrator) = &value.integrator {
+            options.integrator = IntegratorType::from_str(integrator)?;
+        }
+        options.spectral = value.spectral;
+        if let Some(filter) = &value.filter {
+            options.filter = FilterType::from_str(filter)?;
+        }
+        options.filter_radius = value
+            .filter_radius
+            .unwrap_or(options.filter.default_radius());
+        options.region = value.region.map(|[x, y, width, height]| {
+            RenderRegion::new(x, y, width, height)
+        });
+        options.crop_to_region = value.crop;
+        Ok(options)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct JobCreatedModel {
+    pub id: JobId,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct JobStatusModel {
+    pub id: JobId,
+    /// `queued`, `rendering`, `completed`, `failed` or `cancelled`.
+    pub state: String,
+    pub completed_samples: u16,
+    pub samples_per_pixel: u16,
+    /// Completed part of the render between zero and one.
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<RenderStatsModel>,
+}
+
+impl From<&JobStatus> for JobStatusModel {
+    fn from(value: &JobStatus) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.name().to_string(),
+            completed_samples: value.completed_samples,
+            samples_per_pixel: value.samples_per_pixel,
+            progress: value.progress,
+            error: value.error.clone(),
+            stats: value.stats.as_ref().map(RenderStatsModel::from),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct ErrorModel {
+    pub error: String,
+}